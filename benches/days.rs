@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use std::hint::black_box;
+
+use aoc2019::{
+    day01::Day01,
+    day02::{self, Day02},
+    day03::Day03,
+    day04::Day04,
+    day05::Day05,
+    day06::Day06,
+    day07::Day07,
+    day08::Day08,
+    solution::{self, cache, PuzzleInput, Solution},
+};
+
+/// Benchmarks parsing and both parts of `S` against its committed input.
+fn day<S: Solution>(c: &mut Criterion) {
+    let (raw, input) = solution::load::<S>(&PuzzleInput::Repo).expect("Couldn't load input");
+    let mut group = c.benchmark_group(format!("day{:02}", S::DAY));
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&raw))));
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&input))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&input))));
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    day::<Day01>(c);
+    day::<Day02>(c);
+    day::<Day03>(c);
+    day::<Day04>(c);
+    day::<Day05>(c);
+    day::<Day06>(c);
+    day::<Day07>(c);
+    day::<Day08>(c);
+}
+
+/// Day 2 part 2 solved by solving for noun and verb, and, with the
+/// `include_slow` feature, by brute force.
+fn day02_strategies(c: &mut Criterion) {
+    let (_, input) = solution::load::<Day02>(&PuzzleInput::Repo).expect("Couldn't load input");
+    let mut group = c.benchmark_group("day02_part2");
+    group.bench_function("linear", |b| {
+        b.iter(|| day02::stage2_linear(black_box(&input)))
+    });
+    #[cfg(feature = "include_slow")]
+    group.bench_function("brute_force", |b| {
+        b.iter(|| day02::stage2(black_box(&input)))
+    });
+    group.finish();
+}
+
+/// Day 3's input parsed from scratch against loaded from a warm parse
+/// cache, which has to stay the faster of the two to be worth having.
+fn parse_cache(c: &mut Criterion) {
+    let (raw, input) = solution::load::<Day03>(&PuzzleInput::Repo).expect("Couldn't load input");
+    let dir = std::env::temp_dir().join(format!("aoc-bench-cache-{}", std::process::id()));
+    cache::put::<Day03>(&dir, &raw, &input).expect("Couldn't write the parse cache");
+    let mut group = c.benchmark_group("day03_parse_cache");
+    group.bench_function("parse", |b| b.iter(|| Day03::parse(black_box(&raw))));
+    group.bench_function("hit", |b| {
+        b.iter(|| cache::get::<Day03>(&dir, black_box(&raw)).expect("Cache miss"))
+    });
+    group.finish();
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+criterion_group!(benches, days, day02_strategies, parse_cache);
+criterion_main!(benches);
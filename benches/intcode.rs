@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use std::hint::black_box;
+
+use aoc2019::intcode::{
+    amplifier::{self, Topology},
+    Operation, Program,
+};
+
+/// Counts a cell at 20 down from 100000, multiplying into 21 each time.
+const COUNTDOWN: &str = "1101,100000,0,20,1001,20,-1,20,1002,20,3,21,1005,20,4,99,0,0,0,0,0,0";
+
+/// Echoes each input until it reads 0.
+const ECHO: &str = "3,9,4,9,1005,9,0,99,0,0";
+
+const AMPLIFIER: &str =
+    "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+
+fn decode(c: &mut Criterion) {
+    let p: Program = "1002,4,3,4,33".parse().unwrap();
+    c.bench_function("intcode/decode", |b| {
+        b.iter(|| Operation::decode(black_box(&p), black_box(0)))
+    });
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let p: Program = COUNTDOWN.parse().unwrap();
+    c.bench_function("intcode/arithmetic_loop", |b| {
+        b.iter(|| black_box(p.clone()).interpret())
+    });
+}
+
+fn io(c: &mut Criterion) {
+    let p: Program = ECHO.parse().unwrap();
+    c.bench_function("intcode/echo_10000", |b| {
+        b.iter(|| black_box(p.clone()).interpret_input((0..10000).rev()))
+    });
+}
+
+fn amplifiers(c: &mut Criterion) {
+    let p: Program = AMPLIFIER.parse().unwrap();
+    c.bench_function("intcode/amplifier_feedback_search", |b| {
+        b.iter(|| amplifier::search(black_box(&p), &[5, 6, 7, 8, 9], Topology::Feedback))
+    });
+}
+
+criterion_group!(benches, decode, arithmetic, io, amplifiers);
+criterion_main!(benches);
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use std::{fmt, fs, io, path::Path};
+
+use crate::solution::DayResult;
+
+/// Default location of the recorded answers, relative to the repo root.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known-good answers, keyed by day, part and the hash of the input they
+/// were computed from, so different people's inputs can share one file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, rename = "answer")]
+    answers: Vec<Answer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    /// `solution::input_hash` of the input, as 16 hex digits.
+    pub input: String,
+    pub value: String,
+}
+
+/// A computed answer that didn't match the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {} part {}: expected {:?}, got {:?}",
+            self.day, self.part, self.expected, self.actual
+        )
+    }
+}
+
+fn hash_key(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+impl Answers {
+    /// Loads the answers file, treating a missing file as empty.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(s) => toml::from_str(&s).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let s = toml::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        fs::write(path, s)
+    }
+
+    pub fn get(&self, day: u8, part: u8, input_hash: u64) -> Option<&str> {
+        let input = hash_key(input_hash);
+        self.answers
+            .iter()
+            .find(|a| a.day == day && a.part == part && a.input == input)
+            .map(|a| a.value.as_str())
+    }
+
+    /// Stores every answer in `result`, replacing any previous answer for the
+    /// same day, part and input.
+    pub fn record(&mut self, result: &DayResult) {
+        let input = hash_key(result.input_hash);
+        for part in &result.parts {
+            self.answers
+                .retain(|a| !(a.day == result.day && a.part == part.part && a.input == input));
+            self.answers.push(Answer {
+                day: result.day,
+                part: part.part,
+                input: input.clone(),
+                value: part.answer.clone(),
+            });
+        }
+        self.answers
+            .sort_by(|a, b| (a.day, a.part, &a.input).cmp(&(b.day, b.part, &b.input)));
+    }
+
+    /// Compares `result` against the recorded answers. Parts with nothing
+    /// recorded for this input are skipped and returned separately.
+    pub fn check(&self, result: &DayResult) -> (Vec<Mismatch>, Vec<u8>) {
+        let mut mismatches = Vec::new();
+        let mut unknown = Vec::new();
+        for part in &result.parts {
+            match self.get(result.day, part.part, result.input_hash) {
+                Some(expected) if expected != part.answer => mismatches.push(Mismatch {
+                    day: result.day,
+                    part: part.part,
+                    expected: expected.to_string(),
+                    actual: part.answer.clone(),
+                }),
+                Some(_) => {}
+                None => unknown.push(part.part),
+            }
+        }
+        (mismatches, unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::solution::PartResult;
+
+    use std::time::Duration;
+
+    fn result(answers: &[&str]) -> DayResult {
+        DayResult {
+            day: 3,
+            input_hash: 0xabc,
+            parse_time: Duration::default(),
+            parts: answers
+                .iter()
+                .enumerate()
+                .map(|(i, a)| PartResult {
+                    part: i as u8 + 1,
+                    answer: a.to_string(),
+                    time: Duration::default(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_record_and_check() {
+        let mut answers = Answers::default();
+        answers.record(&result(&["1", "2"]));
+        answers.record(&result(&["1", "3"]));
+        assert_eq!(answers.answers.len(), 2);
+        assert_eq!(answers.get(3, 2, 0xabc), Some("3"));
+
+        let (mismatches, unknown) = answers.check(&result(&["1", "4"]));
+        assert_eq!(
+            mismatches,
+            vec![Mismatch {
+                day: 3,
+                part: 2,
+                expected: "3".to_string(),
+                actual: "4".to_string(),
+            }]
+        );
+        assert!(unknown.is_empty());
+
+        let other_input = DayResult {
+            input_hash: 0xdef,
+            ..result(&["1", "3"])
+        };
+        assert_eq!(answers.check(&other_input), (vec![], vec![1, 2]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.record(&result(&["1", "\n# #\n ##"]));
+        let s = toml::to_string_pretty(&answers).unwrap();
+        let loaded: Answers = toml::from_str(&s).unwrap();
+        assert_eq!(loaded.answers, answers.answers);
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fmt, fs,
+    hint::black_box,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::solution::{self, cache, Parts, PuzzleInput, Solution};
+
+/// Summary of repeated timings of one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "Need at least one sample");
+        samples.sort();
+        let runs = samples.len();
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+        let mean = samples.iter().sum::<Duration>() / runs as u32;
+        let variance = if runs > 1 {
+            samples
+                .iter()
+                .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+                .sum::<f64>()
+                / (runs - 1) as f64
+        } else {
+            0.0
+        };
+        Self {
+            runs,
+            min: samples[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Timings of one day's parse, part 1 and part 2.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub stages: Vec<(Stage, Stats)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        })
+    }
+}
+
+fn sample<T>(warmup: usize, runs: usize, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+    let mut samples: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(&mut samples)
+}
+
+/// Times parsing and the selected parts of a day `runs` times, after
+/// `warmup` untimed runs. With the parse cache enabled, parsing means
+/// loading the cached input.
+pub fn run<S: Solution>(
+    source: &PuzzleInput,
+    parts: Parts,
+    warmup: usize,
+    runs: usize,
+) -> io::Result<DayBench> {
+    let (raw, input) = solution::load::<S>(source)?;
+    let mut stages = vec![(
+        Stage::Parse,
+        sample(warmup, runs, || cache::parse::<S>(&raw)),
+    )];
+    if parts.part1 {
+        stages.push((Stage::Part1, sample(warmup, runs, || S::part1(&input))));
+    }
+    if parts.part2 {
+        stages.push((Stage::Part2, sample(warmup, runs, || S::part2(&input))));
+    }
+    Ok(DayBench {
+        day: S::DAY,
+        stages,
+    })
+}
+
+/// A saved set of benchmark results to compare later runs against.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub days: Vec<DayBench>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s = fs::read_to_string(path)?;
+        serde_json::from_str(&s).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&Stats> {
+        self.days
+            .iter()
+            .filter(|d| d.day == day)
+            .flat_map(|d| d.stages.iter())
+            .find(|(s, _)| *s == stage)
+            .map(|(_, stats)| stats)
+    }
+}
+
+/// Relative change of the median against the baseline, in percent.
+pub fn change(baseline: &Stats, current: &Stats) -> f64 {
+    if baseline.median == Duration::default() {
+        return 0.0;
+    }
+    (current.median.as_secs_f64() / baseline.median.as_secs_f64() - 1.0) * 100.0
+}
+
+/// Prints one row per stage, comparing medians to `baseline` when given.
+/// Returns how many stages regressed by more than `threshold` percent.
+pub fn write(
+    out: &mut impl Write,
+    benches: &[DayBench],
+    baseline: Option<&Baseline>,
+    threshold: f64,
+) -> io::Result<usize> {
+    let mut regressions = 0;
+    writeln!(
+        out,
+        "{:>3}  {:<5}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}  {:>9}",
+        "day", "stage", "runs", "min", "median", "mean", "stddev", "change"
+    )?;
+    for bench in benches {
+        for (stage, stats) in &bench.stages {
+            let compared = baseline.and_then(|b| b.get(bench.day, *stage));
+            let change = compared.map(|b| change(b, stats));
+            let regressed = change.is_some_and(|c| c > threshold);
+            if regressed {
+                regressions += 1;
+            }
+            let line = format!(
+                "{:>3}  {:<5}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}  {:>9}{}",
+                bench.day,
+                stage.to_string(),
+                stats.runs,
+                format!("{:.1?}", stats.min),
+                format!("{:.1?}", stats.median),
+                format!("{:.1?}", stats.mean),
+                format!("{:.1?}", stats.stddev),
+                change.map_or("".to_string(), |c| format!("{:+.1}%", c)),
+                if regressed { "  REGRESSION" } else { "" }
+            );
+            writeln!(out, "{}", line.trim_end())?;
+        }
+    }
+    Ok(regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&mut [ms(4), ms(2), ms(6), ms(8)]);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+        // Sample standard deviation of 2, 4, 6, 8 is sqrt(20 / 3).
+        assert!((stats.stddev.as_secs_f64() * 1000.0 - (20.0f64 / 3.0).sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let stats = |median| Stats::from_samples(&mut [ms(median)]);
+        let baseline = Baseline {
+            days: vec![DayBench {
+                day: 1,
+                stages: vec![(Stage::Part1, stats(10)), (Stage::Part2, stats(10))],
+            }],
+        };
+        let current = vec![DayBench {
+            day: 1,
+            stages: vec![(Stage::Part1, stats(11)), (Stage::Part2, stats(13))],
+        }];
+        let mut out = Vec::new();
+        assert_eq!(write(&mut out, &current, Some(&baseline), 20.0).unwrap(), 1);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.lines().nth(1).unwrap().ends_with("+10.0%"));
+        assert!(out.lines().nth(2).unwrap().ends_with("+30.0%  REGRESSION"));
+    }
+}
@@ -1,72 +1,86 @@
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
 
-use std::{fs, path::Path, str::FromStr};
+pub mod report;
+pub mod rocket;
 
-const DAY: u64 = 1;
+use rocket::{Formula, Fuel, Mass};
 
-pub fn stage1(input: &Vec<u64>) -> u64 {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
-    input.iter().map(|n| n / 3 - 2).sum()
+fn module_fuel(m: &u64) -> u64 {
+    Formula::ROCKET.fuel(Mass(*m)).0
 }
 
-fn fuel(n: &i32) -> i32 {
-    n / 3 - 2
+#[tracing::instrument(skip_all)]
+pub fn stage1(input: &Vec<u64>) -> u64 {
+    input.iter().map(|m| Fuel(module_fuel(m))).sum::<Fuel>().0
 }
 
 fn module_total_fuel(m: &u64) -> u64 {
-    let mut mass = *m;
-    let mut total_fuel = 0;
-    loop {
-        let fuel_mass = fuel(&(mass as i32));
-        if fuel_mass <= 0 {
-            break;
-        }
-        total_fuel += fuel_mass as u64;
-        // println!("{:?}", total_fuel);
-        mass = fuel_mass as u64;
-    }
-    total_fuel
+    Formula::ROCKET.total_fuel(Mass(*m)).0
 }
 
+/// How many rounds of fuel `module_total_fuel` adds up.
+fn module_fuel_steps(m: &u64) -> usize {
+    Formula::ROCKET.fuel_steps(Mass(*m)).count()
+}
+
+#[tracing::instrument(skip_all)]
 pub fn stage2(input: &Vec<u64>) -> u64 {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
-    let v = input.iter().map(module_total_fuel);
-    v.collect::<Vec<u64>>().iter().sum::<u64>()
+    input
+        .iter()
+        .map(|&m| Fuel(module_total_fuel(&m)))
+        .sum::<Fuel>()
+        .0
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day1");
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let s = fs::read_to_string(input_path).expect("Some input needs to exist");
-    let input: Vec<u64> = s
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(u64::from_str)
-                .map(Result::unwrap)
-                .collect::<Vec<u64>>()
-        })
-        .flatten()
-        .collect();
-    let s1 = stage1(&input);
-    log::info!("{:?}", s1);
-    let s2 = stage2(&input);
-    log::info!("{:?}", s2);
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u64>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::whitespace_separated(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        stage1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        stage2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use proptest::prelude::*;
+
     #[test]
     fn test_total_fuel() {
         assert_eq!(module_total_fuel(&1969), 966);
+        assert_eq!(module_total_fuel(&u64::MAX), 9_223_372_036_854_775_669);
+    }
+
+    proptest! {
+        #[test]
+        fn module_total_fuel_is_the_recursion(mass in any::<u64>()) {
+            let (mut fuel, mut total) = (i128::from(mass), 0);
+            loop {
+                fuel = fuel / 3 - 2;
+                if fuel <= 0 {
+                    break;
+                }
+                total += fuel;
+            }
+            prop_assert_eq!(i128::from(module_total_fuel(&mass)), total);
+        }
     }
 }
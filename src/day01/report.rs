@@ -0,0 +1,215 @@
+use serde::Serialize;
+
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
+
+use super::{module_fuel, module_fuel_steps, module_total_fuel, rocket::Fuel};
+use crate::report::Format;
+
+/// The fuel one module needs, both for its own mass and with the fuel's
+/// mass included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ModuleFuel {
+    /// Position of the module in the input, from 1.
+    pub module: usize,
+    pub mass: u64,
+    /// Fuel for the module's mass alone, as in part 1.
+    pub direct: u64,
+    /// Fuel including the fuel for the fuel, as in part 2.
+    pub total: u64,
+    /// How many rounds of fuel make up `total`.
+    pub steps: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Totals {
+    pub modules: usize,
+    pub mass: u128,
+    pub direct: u64,
+    pub total: u64,
+    pub steps: usize,
+}
+
+/// What to order the modules of a report by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Module,
+    Mass,
+    Direct,
+    Total,
+    Steps,
+}
+
+pub const SORT_KEYS: &[&str] = &["module", "mass", "direct", "total", "steps"];
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "module" => Ok(SortKey::Module),
+            "mass" => Ok(SortKey::Mass),
+            "direct" => Ok(SortKey::Direct),
+            "total" => Ok(SortKey::Total),
+            "steps" => Ok(SortKey::Steps),
+            _ => Err(format!(
+                "Unknown sort key {:?}, expected one of {:?}",
+                s, SORT_KEYS
+            )),
+        }
+    }
+}
+
+/// Fuel for every module of the input, worked out with the same functions
+/// as the two parts so the totals always match the answers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FuelReport {
+    pub modules: Vec<ModuleFuel>,
+}
+
+impl FuelReport {
+    pub fn new(masses: &[u64]) -> Self {
+        let modules = masses
+            .iter()
+            .enumerate()
+            .map(|(i, mass)| ModuleFuel {
+                module: i + 1,
+                mass: *mass,
+                direct: module_fuel(mass),
+                total: module_total_fuel(mass),
+                steps: module_fuel_steps(mass),
+            })
+            .collect();
+        FuelReport { modules }
+    }
+
+    /// Panics if the fuel doesn't fit in a `u64`, same as the parts.
+    pub fn totals(&self) -> Totals {
+        Totals {
+            modules: self.modules.len(),
+            mass: self.modules.iter().map(|m| u128::from(m.mass)).sum(),
+            direct: self.modules.iter().map(|m| Fuel(m.direct)).sum::<Fuel>().0,
+            total: self.modules.iter().map(|m| Fuel(m.total)).sum::<Fuel>().0,
+            steps: self.modules.iter().map(|m| m.steps).sum(),
+        }
+    }
+
+    /// Orders the modules by `key`, smallest first unless `reverse`, keeping
+    /// input order between equals either way.
+    pub fn sort(&mut self, key: SortKey, reverse: bool) {
+        let value = |m: &ModuleFuel| match key {
+            SortKey::Module => m.module as u64,
+            SortKey::Mass => m.mass,
+            SortKey::Direct => m.direct,
+            SortKey::Total => m.total,
+            SortKey::Steps => m.steps as u64,
+        };
+        self.modules.sort_by(|a, b| {
+            let order = value(a).cmp(&value(b));
+            if reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+
+    pub fn write(&self, out: &mut impl Write, format: Format) -> io::Result<()> {
+        let totals = self.totals();
+        match format {
+            Format::Table => {
+                writeln!(
+                    out,
+                    "{:>6}  {:>12}  {:>12}  {:>12}  {:>5}",
+                    "module", "mass", "direct", "total", "steps"
+                )?;
+                for m in &self.modules {
+                    writeln!(
+                        out,
+                        "{:>6}  {:>12}  {:>12}  {:>12}  {:>5}",
+                        m.module, m.mass, m.direct, m.total, m.steps
+                    )?;
+                }
+                writeln!(
+                    out,
+                    "{:>6}  {:>12}  {:>12}  {:>12}  {:>5}",
+                    "total", totals.mass, totals.direct, totals.total, totals.steps
+                )
+            }
+            Format::Json => {
+                serde_json::to_writer_pretty(
+                    &mut *out,
+                    &serde_json::json!({ "modules": self.modules, "totals": totals }),
+                )?;
+                writeln!(out)
+            }
+            Format::Csv => {
+                writeln!(out, "module,mass,direct,total,steps")?;
+                for m in &self.modules {
+                    writeln!(
+                        out,
+                        "{},{},{},{},{}",
+                        m.module, m.mass, m.direct, m.total, m.steps
+                    )?;
+                }
+                writeln!(
+                    out,
+                    "total,{},{},{},{}",
+                    totals.mass, totals.direct, totals.total, totals.steps
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::day01::{stage1, stage2};
+
+    const MASSES: &[u64] = &[1969, 12, 100756, 14];
+
+    fn render(report: &FuelReport, format: Format) -> String {
+        let mut out = Vec::new();
+        report.write(&mut out, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_totals_match_the_parts() {
+        let totals = FuelReport::new(MASSES).totals();
+        assert_eq!(totals.direct, stage1(&MASSES.to_vec()));
+        assert_eq!(totals.total, stage2(&MASSES.to_vec()));
+        assert_eq!(totals.mass, 102751);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut report = FuelReport::new(MASSES);
+        assert_eq!(report.modules[0].steps, 5);
+        report.sort(SortKey::Total, true);
+        let order: Vec<usize> = report.modules.iter().map(|m| m.module).collect();
+        assert_eq!(order, vec![3, 1, 2, 4]);
+        report.sort(SortKey::Module, false);
+        assert_eq!(report, FuelReport::new(MASSES));
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render(&FuelReport::new(&[1969, 12]), Format::Csv),
+            "module,mass,direct,total,steps\n1,1969,654,966,5\n2,12,2,2,1\ntotal,1981,656,968,6\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&FuelReport::new(MASSES), Format::Json)).unwrap();
+        assert_eq!(json["modules"][2]["total"], 50346);
+        assert_eq!(json["totals"]["modules"], 4);
+    }
+}
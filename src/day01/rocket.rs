@@ -0,0 +1,144 @@
+use std::{iter, ops::Add};
+
+/// Mass of a module, or of the fuel carried for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mass(pub u64);
+
+/// Fuel needed to launch some mass. Fuel weighs something too, so it converts
+/// into `Mass`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fuel(pub u64);
+
+impl From<Fuel> for Mass {
+    fn from(fuel: Fuel) -> Self {
+        Mass(fuel.0)
+    }
+}
+
+impl Fuel {
+    pub fn checked_add(self, other: Fuel) -> Option<Fuel> {
+        self.0.checked_add(other.0).map(Fuel)
+    }
+}
+
+/// Panics instead of wrapping when the total doesn't fit in a `u64`.
+impl Add for Fuel {
+    type Output = Fuel;
+
+    fn add(self, other: Fuel) -> Fuel {
+        self.checked_add(other).expect("Total fuel overflows u64")
+    }
+}
+
+impl iter::Sum for Fuel {
+    fn sum<I: Iterator<Item = Fuel>>(iter: I) -> Fuel {
+        iter.fold(Fuel::default(), Add::add)
+    }
+}
+
+/// Fuel for a mass is the mass divided by `divisor`, rounded down, minus
+/// `subtract`, and never less than nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Formula {
+    divisor: u64,
+    subtract: u64,
+}
+
+impl Formula {
+    /// The formula from the puzzle.
+    pub const ROCKET: Formula = Formula::new(3, 2);
+
+    /// Panics unless `divisor` is at least 2. Fuel then weighs at most half
+    /// of what it launches, so `total_fuel` comes to an end within a few
+    /// dozen steps.
+    pub const fn new(divisor: u64, subtract: u64) -> Self {
+        assert!(divisor >= 2, "Fuel needs a divisor of at least 2");
+        Formula { divisor, subtract }
+    }
+
+    pub fn fuel(&self, mass: Mass) -> Fuel {
+        Fuel((mass.0 / self.divisor).saturating_sub(self.subtract))
+    }
+
+    /// Fuel for `mass`, then fuel for that fuel and so on, up to the first
+    /// amount that needs none.
+    pub fn fuel_steps(&self, mass: Mass) -> impl Iterator<Item = Fuel> + '_ {
+        iter::successors(Some(self.fuel(mass)), move |&fuel| {
+            Some(self.fuel(fuel.into()))
+        })
+        .take_while(|&fuel| fuel != Fuel::default())
+    }
+
+    /// All the fuel `mass` needs once the fuel's own mass is accounted for.
+    pub fn total_fuel(&self, mass: Mass) -> Fuel {
+        self.fuel_steps(mass).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    /// The recursion straight from the puzzle, in wider integers so it can't
+    /// overflow on its own.
+    fn recursive(mass: u64, divisor: u64, subtract: u64) -> u128 {
+        let fuel = (i128::from(mass) / i128::from(divisor)) - i128::from(subtract);
+        if fuel <= 0 {
+            0
+        } else {
+            fuel as u128 + recursive(fuel as u64, divisor, subtract)
+        }
+    }
+
+    #[test]
+    fn test_rocket() {
+        assert_eq!(Formula::ROCKET.fuel(Mass(14)), Fuel(2));
+        assert_eq!(Formula::ROCKET.fuel(Mass(5)), Fuel(0));
+        assert_eq!(Formula::ROCKET.total_fuel(Mass(100756)), Fuel(50346));
+        assert_eq!(
+            Formula::ROCKET.fuel_steps(Mass(1969)).collect::<Vec<_>>(),
+            [654, 216, 70, 21, 5]
+                .iter()
+                .map(|&f| Fuel(f))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor of at least 2")]
+    fn test_endless_formula() {
+        Formula::new(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor of at least 2")]
+    fn test_slow_formula() {
+        // Would only shed 5 per step, taking mass / 5 steps.
+        Formula::new(1, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Total fuel overflows u64")]
+    fn test_overflow() {
+        let _: Fuel = vec![Fuel(u64::MAX), Fuel(1)].into_iter().sum();
+    }
+
+    proptest! {
+        #[test]
+        fn any_formula_is_the_recursion(
+            mass in any::<u64>(),
+            divisor in 2..100u64,
+            subtract in 0..100u64,
+        ) {
+            let total = Formula::new(divisor, subtract).total_fuel(Mass(mass));
+            prop_assert_eq!(u128::from(total.0), recursive(mass, divisor, subtract));
+        }
+
+        #[test]
+        fn total_fuel_weighs_less_than_the_mass(mass in any::<u64>()) {
+            prop_assert!(Formula::ROCKET.total_fuel(Mass(mass)) <= Fuel(mass / 2));
+        }
+    }
+}
@@ -1,104 +1,66 @@
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
-
-use std::{fs, path::Path, str::FromStr, time::Instant};
-
-use crate::intcode::*;
-
-const DAY: usize = 2;
-
-pub fn stage1(input: &Vec<isize>) -> isize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
+use crate::{
+    intcode::{
+        search::{self, Query},
+        *,
+    },
+    parse::ParseError,
+    solution::Solution,
+};
+
+const DAY: u8 = 2;
+/// The output part 2 is looking for.
+const TARGET: isize = 19690720;
+
+#[tracing::instrument(skip_all)]
+pub fn stage1(input: &Program) -> isize {
     run(input, 12, 2)
 }
 
-fn run(input: &Vec<isize>, noun: isize, verb: isize) -> isize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("run");
-    let mut p = Program::new(input);
+#[tracing::instrument(level = "debug", skip_all)]
+fn run(input: &Program, noun: isize, verb: isize) -> isize {
+    let mut p = input.clone();
     p.load_input(noun, verb);
     p.interpret()
 }
 
-#[cfg(feature = "include_slow")]
-pub fn stage2(input: &Vec<isize>) -> isize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
-    let verb = 0;
-    let noun = {
-        let mut final_noun = 0;
-        for noun in 0..100 {
-            let output = run(input, noun, verb);
-            if output > 19690720 {
-                final_noun = noun - 1;
-                break;
-            }
-            if output == 19690720 {
-                return 100 * noun + verb;
-            }
-        }
-        final_noun
-    };
-
-    for verb in 0..100 {
-        let output = run(input, noun, verb);
-        log::trace!("{}, {} => {}", noun, verb, output);
-        if output == 19690720 {
-            return 100 * noun + verb;
-        }
+fn answer(solutions: &[Vec<isize>]) -> isize {
+    match solutions {
+        [first, ..] => 100 * first[0] + first[1],
+        [] => panic!("Didn't find values"),
     }
-    panic!("Didn't find values")
 }
 
-pub fn stage2_linear(input: &Vec<isize>) -> isize {
-    let n0 = run(input, 0, 0);
-    let n1 = run(input, 1, 0);
-    let v1 = run(input, 0, 1);
-    let n = n1 - n0;
-    let v = v1 - n0;
-
-    let goal = 19690720;
-    let noun = (goal - n0) / n;
-    let verb = (goal - n0 - n * noun) / v;
-    log::trace!("{}, {}", noun, verb);
-    100 * noun + verb
+#[cfg(feature = "include_slow")]
+#[tracing::instrument(skip_all)]
+pub fn stage2(input: &Program) -> isize {
+    answer(&search::brute_force(input, &Query::noun_verb(TARGET)))
+}
+
+pub fn stage2_linear(input: &Program) -> isize {
+    answer(&search::linear(input, &Query::noun_verb(TARGET)).expect("Output isn't linear"))
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day2");
-    let start = Instant::now();
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let input = &fs::read_to_string(input_path).expect("Some input needs to exist");
-
-    let input = input
-        .lines()
-        .map(|s| s.split(',').map(isize::from_str).map(Result::unwrap))
-        .flatten()
-        .collect::<Vec<isize>>();
-
-    log::debug!("Day {} load: {:?}", DAY, start.elapsed());
-
-    let start = Instant::now();
-    let s1 = stage1(&input);
-    log::debug!("Stage 1 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s1);
-
-    let start = Instant::now();
-    let s2 = stage2_linear(&input);
-    log::debug!("Stage 2 linear timer: {:?}", start.elapsed());
-    log::info!("{:?}", s2);
-
-    #[cfg(feature = "include_slow")]
-    {
-        let start = Instant::now();
-        let s2 = stage2(&input);
-        log::debug!("Stage 2 old timer: {:?}", start.elapsed());
-        log::info!("{:?}", s2);
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = DAY;
+
+    type Input = Program;
+    type Part1 = isize;
+    type Part2 = isize;
+
+    fn parse(input: &str) -> Result<Program, ParseError> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(input: &Program) -> isize {
+        stage1(input)
+    }
+
+    fn part2(input: &Program) -> isize {
+        let found = search::search(input, &Query::noun_verb(TARGET));
+        log::debug!("Found {:?} with {:?}", found.solutions, found.strategy);
+        answer(&found.solutions)
     }
 }
 
@@ -106,23 +68,15 @@ pub fn run_day() {
 mod tests {
     use super::*;
 
+    use std::path::Path;
+
     #[test]
     fn test_template() {
         let input_path = Path::new("src")
             .join(format!("day{:02}", DAY))
             .join("input");
         log::debug!("Opening file {:?}", input_path);
-        let s = fs::read_to_string(input_path).expect("Some input needs to exist");
-        let input: Vec<isize> = s
-            .lines()
-            .map(|line| {
-                line.split(",")
-                    .map(isize::from_str)
-                    .map(Result::unwrap)
-                    .collect::<Vec<isize>>()
-            })
-            .flatten()
-            .collect();
+        let input = Program::from_file(input_path).expect("Some input needs to exist");
         let n0 = run(&input, 0, 0);
         let n1 = run(&input, 1, 0);
         let v1 = run(&input, 0, 1);
@@ -135,4 +89,13 @@ mod tests {
 
         assert_eq!(1, 1);
     }
+
+    #[test]
+    fn test_search() {
+        let input = Program::from_file(Path::new("src/day02/input")).unwrap();
+        let found = search::search(&input, &Query::noun_verb(TARGET));
+        assert_eq!(found.strategy, search::Strategy::Linear);
+        assert_eq!(found.solutions.len(), 1);
+        assert_eq!(answer(&found.solutions), stage2_linear(&input));
+    }
 }
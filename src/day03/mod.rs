@@ -1,21 +1,19 @@
+use serde::{Deserialize, Serialize};
 use sorted_vec::SortedVec;
 
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
-
 use std::{
-    cmp::{min, Ord, Ordering, PartialEq},
+    cmp::{Ord, Ordering, PartialEq},
     collections::HashSet,
-    fs,
-    path::Path,
-    time::Instant,
 };
 
-const DAY: u64 = 3;
+use crate::{
+    parse::{self, Direction, ParseError, Step},
+    solution::{cache, Solution},
+};
 
 type Point = (isize, isize);
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 // enum Line {
 //     Horiz(Point, usize),
 //     Vert(Point, usize),
@@ -28,43 +26,33 @@ struct Line {
 }
 
 impl Line {
-    fn build(p: Point, s: &String) -> (Self, Point) {
-        match s.chars().next().unwrap() {
-            'R' => {
-                let this = Self {
-                    vert: false,
-                    p,
-                    d: usize::from_str_radix(&s[1..], 10).unwrap(),
-                };
+    fn build(p: Point, step: Step) -> (Self, Point) {
+        let d = step.distance;
+        match step.direction {
+            Direction::Right => {
+                let this = Self { vert: false, p, d };
                 (this, this.end())
             }
-            'L' => {
-                let d = isize::from_str_radix(&s[1..], 10).unwrap();
+            Direction::Left => {
                 let this = Self {
                     vert: false,
-                    p: (p.0 - d, p.1),
-                    d: d as usize,
+                    p: (p.0 - d as isize, p.1),
+                    d,
                 };
                 (this, this.start())
             }
-            'U' => {
-                let this = Self {
-                    vert: true,
-                    p,
-                    d: usize::from_str_radix(&s[1..], 10).unwrap(),
-                };
+            Direction::Up => {
+                let this = Self { vert: true, p, d };
                 (this, this.end())
             }
-            'D' => {
-                let d = isize::from_str_radix(&s[1..], 10).unwrap();
+            Direction::Down => {
                 let this = Self {
                     vert: true,
-                    p: (p.0, p.1 - d),
-                    d: d as usize,
+                    p: (p.0, p.1 - d as isize),
+                    d,
                 };
                 (this, this.start())
             }
-            _ => panic!("Doesn't work"),
         }
     }
 
@@ -105,7 +93,7 @@ impl Line {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 struct Start(Line);
 
 impl Ord for Start {
@@ -120,7 +108,7 @@ impl PartialOrd for Start {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 struct End(Line);
 
 impl Ord for End {
@@ -135,7 +123,7 @@ impl PartialOrd for End {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Panel {
     wires: Vec<Vec<Line>>,
     idx: usize,
@@ -158,15 +146,14 @@ impl Panel {
     }
 
     #[inline]
-    fn construct_wire(mut self, wire: Vec<String>) -> Self {
-        #[cfg(feature = "profiler")]
-        profile_scope!("construct_wire");
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn construct_wire(mut self, wire: Vec<Step>) -> Self {
         let mut verts = SortedVec::new();
         let mut starts = SortedVec::new();
         let mut ends = SortedVec::new();
 
         for w in wire {
-            self.add_segment(&w, &mut verts, &mut starts, &mut ends);
+            self.add_segment(w, &mut verts, &mut starts, &mut ends);
         }
         self.verts[self.idx].append(&mut verts.to_vec());
         self.starts[self.idx].append(&mut starts.to_vec());
@@ -177,16 +164,15 @@ impl Panel {
     }
 
     #[inline]
+    #[tracing::instrument(level = "debug", skip_all)]
     fn add_segment(
         &mut self,
-        s: &String,
+        step: Step,
         verts: &mut SortedVec<Start>,
         starts: &mut SortedVec<Start>,
         ends: &mut SortedVec<End>,
     ) {
-        #[cfg(feature = "profiler")]
-        profile_scope!("add_segment");
-        let (line, p) = Line::build(self.last_point, s);
+        let (line, p) = Line::build(self.last_point, step);
         self.wires[self.idx].push(line);
         if line.vert {
             verts.insert(Start(line)).unwrap();
@@ -208,59 +194,46 @@ impl Panel {
         self.verts[idx].iter().map(|Start(l)| l.clone()).collect()
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     fn sorted_horiz_ends(&self, idx: usize) -> Vec<Line> {
-        #[cfg(feature = "profiler")]
-        profile_scope!("sorted_horiz_ends");
         self.ends[idx].iter().map(|End(l)| l.clone()).collect()
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     fn sorted_horiz_starts(&self, idx: usize) -> Vec<Line> {
-        #[cfg(feature = "profiler")]
-        profile_scope!("sorted_horiz_starts");
         self.starts[idx].iter().map(|Start(l)| l.clone()).collect()
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     fn intersections(&self, from: usize, to: usize) -> impl Iterator<Item = Point> {
-        #[cfg(feature = "profiler")]
-        profile_scope!("intersections");
         let mut checking: HashSet<Line> = HashSet::with_capacity(1000);
         let mut intersections = Vec::with_capacity(1000);
 
         let starts = self.sorted_horiz_starts(from);
         let ends = self.sorted_horiz_ends(from);
         let verts = self.sorted_verts(to);
-        let (mut start_idx, mut end_idx, mut vert_idx) = (0, 0, 0);
+        let (mut start_idx, mut end_idx) = (0, 0);
 
-        loop {
-            if vert_idx >= verts.len() || start_idx >= starts.len() {
-                break;
-            }
-            let (start_x, end_x, vert_x) = (
-                starts[start_idx].start().0,
-                ends[end_idx].end().0,
-                verts[vert_idx].start().0,
-            );
-            // let min = *[start_x, end_x, vert_x].iter().min().to_owned().unwrap();
-            let min = min(start_x, min(end_x, vert_x));
-            if min == start_x {
+        // Sweep left to right. Horizontals that start or end at a vertical's x
+        // still touch it, so they go in before it's checked and come out after.
+        for vert in verts {
+            let vert_x = vert.start().0;
+            while start_idx < starts.len() && starts[start_idx].start().0 <= vert_x {
                 checking.insert(starts[start_idx]);
                 start_idx += 1;
-            } else if min == end_x {
+            }
+            while end_idx < ends.len() && ends[end_idx].end().0 < vert_x {
                 checking.remove(&ends[end_idx]);
                 end_idx += 1;
-            } else {
-                intersections.append(&mut check_vert(&checking, verts[vert_idx]));
-                vert_idx += 1;
             }
+            intersections.append(&mut check_vert(&checking, vert));
         }
 
         intersections.into_iter()
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     fn steps_to(&self, p: Point, idx: usize) -> usize {
-        #[cfg(feature = "profiler")]
-        profile_scope!("steps_to");
-
         let mut last_point = (0, 0);
         let mut dist = 0;
         for l in self.wires[idx].to_vec() {
@@ -290,21 +263,23 @@ fn manhattan_distance(p: Point) -> usize {
     (p.0.abs() + p.1.abs()) as usize
 }
 
+#[tracing::instrument(skip_all)]
 pub fn stage1(panel: &Panel) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
-    let last = panel.intersections(0, 1).chain(panel.intersections(1, 0));
+    let last = panel
+        .intersections(0, 1)
+        .chain(panel.intersections(1, 0))
+        // Both wires start at the origin, which doesn't count as a crossing.
+        .filter(|p| *p != (0, 0));
     // log::info!("{:?}", last.collect::<Vec<Point>>());
     last.map(manhattan_distance).min().unwrap()
 }
 
+#[tracing::instrument(skip_all)]
 pub fn stage2(panel: &Panel) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
-
     let last: Vec<Point> = panel
         .intersections(0, 1)
         .chain(panel.intersections(1, 0))
+        .filter(|p| *p != (0, 0))
         .collect();
 
     let wire1 = last.iter().map(|p| panel.steps_to(*p, 0));
@@ -313,39 +288,47 @@ pub fn stage2(panel: &Panel) -> usize {
     wire1.zip(wire2).map(|(a, b)| a + b).min().unwrap()
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day3");
-    let start = Instant::now();
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let s = fs::read_to_string(input_path).expect("Some input needs to exist");
-    let input: Vec<Vec<String>> = s
-        .lines()
-        .map(|line| {
-            line.split(",")
-                .map(&str::to_string)
-                .collect::<Vec<String>>()
-        })
-        .collect();
-    let panel = Panel::new();
-    let panel = input
-        .iter()
-        .fold(panel, |p, v| p.construct_wire(v.to_vec()));
-    log::debug!("Day 3 loading timer: {:?}", start.elapsed());
-
-    // log::info!("{:?}", input);
-    let start = Instant::now();
-    let s1 = stage1(&panel);
-    log::debug!("Stage 1 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s1);
-
-    let start = Instant::now();
-    let s2 = stage2(&panel);
-    log::debug!("Stage 2 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s2);
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Panel;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Panel, ParseError> {
+        let paths = parse::paths(input)?;
+        if paths.len() != 2 {
+            // Point at the first extra path, or just past the end for a
+            // missing one.
+            let line = parse::tokens(input, ',')
+                .get(2)
+                .map_or(input.lines().count() + 1, |path| path[0].line);
+            return Err(ParseError::new(
+                line,
+                1,
+                format!("expected 2 wire paths, found {}", paths.len()),
+            ));
+        }
+        Ok(paths.into_iter().fold(Panel::new(), Panel::construct_wire))
+    }
+
+    fn part1(panel: &Panel) -> usize {
+        stage1(panel)
+    }
+
+    fn part2(panel: &Panel) -> usize {
+        stage2(panel)
+    }
+
+    fn to_cache(panel: &Panel) -> Option<Vec<u8>> {
+        cache::encode(panel)
+    }
+
+    fn from_cache(bytes: &[u8]) -> Option<Panel> {
+        cache::decode(bytes)
+    }
 }
 
 #[cfg(test)]
@@ -354,21 +337,27 @@ mod tests {
 
     #[test]
     fn test_line_build() {
-        assert_eq!((-10, 0), Line::build((0, 0), &"L10".to_string()).0.start());
-        assert_eq!((10, 0), Line::build((0, 0), &"R10".to_string()).0.end());
-        assert_eq!((0, 10), Line::build((0, 0), &"U10".to_string()).0.end());
-        assert_eq!((0, -10), Line::build((0, 0), &"D10".to_string()).0.start());
+        assert_eq!(
+            (-10, 0),
+            Line::build((0, 0), "L10".parse().unwrap()).0.start()
+        );
+        assert_eq!((10, 0), Line::build((0, 0), "R10".parse().unwrap()).0.end());
+        assert_eq!((0, 10), Line::build((0, 0), "U10".parse().unwrap()).0.end());
+        assert_eq!(
+            (0, -10),
+            Line::build((0, 0), "D10".parse().unwrap()).0.start()
+        );
     }
 
     #[test]
     fn test_panel_sorted_verts() {
         let p = Panel::new();
         let p = p.construct_wire(vec![
-            "L10".to_string(),
-            "U13".to_string(),
-            "R20".to_string(),
-            "D1".to_string(),
-            "L30".to_string(),
+            "L10".parse().unwrap(),
+            "U13".parse().unwrap(),
+            "R20".parse().unwrap(),
+            "D1".parse().unwrap(),
+            "L30".parse().unwrap(),
         ]);
         assert_eq!(
             vec![-10, 10],
@@ -384,13 +373,13 @@ mod tests {
     fn test_panel_sorted_horiz_ends() {
         let p = Panel::new();
         let p = p.construct_wire(vec![
-            "L10".to_string(),
-            "U13".to_string(),
-            "R20".to_string(),
-            "D1".to_string(),
-            "L30".to_string(),
-            "U30".to_string(),
-            "L30".to_string(),
+            "L10".parse().unwrap(),
+            "U13".parse().unwrap(),
+            "R20".parse().unwrap(),
+            "D1".parse().unwrap(),
+            "L30".parse().unwrap(),
+            "U30".parse().unwrap(),
+            "L30".parse().unwrap(),
         ]);
         println!("{:?}", p.sorted_horiz_ends(0));
         assert_eq!(
@@ -407,13 +396,13 @@ mod tests {
     fn test_panel_sorted_horiz_starts() {
         let p = Panel::new();
         let p = p.construct_wire(vec![
-            "L10".to_string(),
-            "U13".to_string(),
-            "R20".to_string(),
-            "D1".to_string(),
-            "L30".to_string(),
-            "U30".to_string(),
-            "L30".to_string(),
+            "L10".parse().unwrap(),
+            "U13".parse().unwrap(),
+            "R20".parse().unwrap(),
+            "D1".parse().unwrap(),
+            "L30".parse().unwrap(),
+            "U30".parse().unwrap(),
+            "L30".parse().unwrap(),
         ]);
         println!("{:?}", p.sorted_horiz_starts(0));
         assert_eq!(
@@ -425,4 +414,21 @@ mod tests {
                 .collect::<Vec<isize>>()
         );
     }
+
+    #[test]
+    fn test_crossings() {
+        let panel = Day03::parse("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+        assert_eq!(stage1(&panel), 6);
+        assert_eq!(stage2(&panel), 30);
+    }
+
+    #[test]
+    fn test_wire_count() {
+        let e = Day03::parse("R8,U5\nU7,R6\n\nL4,D2\n").unwrap_err();
+        assert_eq!((e.line, e.column), (4, 1));
+        assert_eq!(e.message, "expected 2 wire paths, found 3");
+
+        let e = Day03::parse("R8,U5\n").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+    }
 }
@@ -1,8 +1,10 @@
 use itertools::Itertools;
-use std::time::Instant;
+use std::ops::RangeInclusive;
 
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
 
 fn digits(x: usize) -> impl DoubleEndedIterator<Item = usize> {
     let digit_count = (x as f32).log10() as usize + 1;
@@ -93,15 +95,12 @@ fn matches_2(&i: &usize) -> Result<(), usize> {
     }
 }
 
-pub fn stage1(_input: &str) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
-
-    // let range = 108457..=562041;
+#[tracing::instrument(skip_all)]
+pub fn stage1(input: &RangeInclusive<usize>) -> usize {
     let mut count = 0;
 
-    let mut x = 108457;
-    while x <= 562041 {
+    let mut x = *input.start();
+    while x <= *input.end() {
         let m = matches(&x);
         if m.is_ok() {
             count += 1;
@@ -114,16 +113,12 @@ pub fn stage1(_input: &str) -> usize {
     count
 }
 
-pub fn stage2(_input: &str) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
-
-    // let range = 108457..=562041;
-
+#[tracing::instrument(skip_all)]
+pub fn stage2(input: &RangeInclusive<usize>) -> usize {
     let mut count = 0;
 
-    let mut x = 108457;
-    while x <= 562041 {
+    let mut x = *input.start();
+    while x <= *input.end() {
         let m = matches_2(&x);
         if m.is_ok() {
             count += 1;
@@ -136,20 +131,32 @@ pub fn stage2(_input: &str) -> usize {
     count
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day1");
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = RangeInclusive<usize>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    // log::info!("{:?}", input);
-    let start = Instant::now();
-    let s1 = stage1("");
-    log::debug!("Stage 1 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s1);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        match parse::tokens(input, '-').as_slice() {
+            [line] => match line.as_slice() {
+                [low, high] => Ok(low.parse("a number")?..=high.parse("a number")?),
+                _ => Err(line[0].error("expected a range like 108457-562041")),
+            },
+            _ => Err(ParseError::new(1, 1, "expected a single range")),
+        }
+    }
 
-    let start = Instant::now();
-    let s2 = stage2("");
-    log::debug!("Stage 2 linear timer: {:?}", start.elapsed());
-    log::info!("{:?}", s2);
+    fn part1(input: &Self::Input) -> usize {
+        stage1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        stage2(input)
+    }
 }
 
 #[cfg(test)]
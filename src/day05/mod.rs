@@ -1,74 +1,44 @@
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
+use crate::{intcode::*, parse::ParseError, solution::Solution};
 
-use std::{
-    fs,
-    path::Path,
-    str::FromStr,
-    time::Instant,
-};
-
-use crate::intcode::*;
-
-const DAY: u64 = 5;
-
-fn run(input: &Vec<isize>, i: Vec<isize>) -> Vec<isize> {
-    let mut p = Program::new(input);
+fn run(input: &Program, i: Vec<isize>) -> Vec<isize> {
+    let mut p = input.clone();
     p.interpret_input(i.into_iter())
 }
 
-pub fn stage1(input: &Vec<isize>) -> isize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
+#[tracing::instrument(skip_all)]
+pub fn stage1(input: &Program) -> isize {
     let output = run(input, vec![1]);
     log::info!("{:?}", output);
 
     *output.last().unwrap()
 }
 
-pub fn stage2(input: &Vec<isize>) -> isize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
+#[tracing::instrument(skip_all)]
+pub fn stage2(input: &Program) -> isize {
     // run(input)
     let output = run(input, vec![5]);
     log::info!("{:?}", output);
     *output.last().unwrap()
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day1");
+pub struct Day05;
 
-    let start = Instant::now();
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let input = &fs::read_to_string(input_path).expect("Some input needs to exist");
+impl Solution for Day05 {
+    const DAY: u8 = 5;
 
-    let input = input
-        .lines()
-        .map(|s| s.split(',').map(isize::from_str).map(Result::unwrap))
-        .flatten()
-        .collect::<Vec<isize>>();
-    log::debug!("Day {} loading timer: {:?}", DAY, start.elapsed());
-    // log::info!("{:?}", input);
+    type Input = Program;
+    type Part1 = isize;
+    type Part2 = isize;
 
-    let start = Instant::now();
-    let s1 = stage1(&input);
-    log::debug!("Stage 1 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s1);
-
-    let start = Instant::now();
-    let s2 = stage2(&input);
-    log::debug!("Stage 2 linear timer: {:?}", start.elapsed());
-    log::info!("{:?}", s2);
-}
+    fn parse(input: &str) -> Result<Program, ParseError> {
+        Ok(input.parse()?)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn part1(input: &Program) -> isize {
+        stage1(input)
+    }
 
-    #[test]
-    fn test_next_passing() {}
+    fn part2(input: &Program) -> isize {
+        stage2(input)
+    }
 }
@@ -1,32 +1,31 @@
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
-
 use vec_tree::{Index, VecTree};
 
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs,
-    path::Path,
-};
+use std::collections::{HashMap, HashSet, VecDeque};
 
-const DAY: usize = 6;
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
+};
 
-type INPUT = (VecTree<String>, Index, Index);
+/// The orbit tree, with SAN and YOU if the map has them. Only part 2 needs
+/// them, so a map without them still has a part 1.
+type INPUT = (VecTree<String>, Option<Index>, Option<Index>);
 
+#[tracing::instrument(skip_all)]
 pub fn stage1(tree: &INPUT) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage1");
-
     tree.0
         .descendants_with_depth(tree.0.get_root_index().unwrap())
         .map(|n| n.1 as u32)
         .sum::<u32>() as usize
 }
 
+#[tracing::instrument(skip_all)]
 pub fn stage2(input: &INPUT) -> usize {
-    #[cfg(feature = "profiler")]
-    profile_scope!("stage2");
     let (tree, santa_node, you_node) = input;
+    let (santa_node, you_node) = match (santa_node, you_node) {
+        (Some(santa), Some(you)) => (santa, you),
+        _ => panic!("Part 2 needs both YOU and SAN in the orbit map"),
+    };
     let mut ans = tree.ancestors(*you_node);
     ans.next().unwrap();
     let yous: HashSet<Index> = ans.collect();
@@ -35,9 +34,7 @@ pub fn stage2(input: &INPUT) -> usize {
     let santas: HashSet<Index> = ans.collect();
     let int = santas.intersection(&yous).cloned().collect();
     let all = santas.difference(&int).chain(yous.difference(&int));
-    log::info!("{:?}", all.count());
-    // all.court()
-    0
+    all.count()
 }
 
 fn build_tree(map: HashMap<String, Vec<String>>) -> INPUT {
@@ -67,44 +64,38 @@ fn build_tree(map: HashMap<String, Vec<String>>) -> INPUT {
             nodes.push_back((child.to_string(), child_node));
         }
     }
-    (tree, santa_node.unwrap(), you_node.unwrap())
+    (tree, santa_node, you_node)
 }
 
-fn parse_input(input: Vec<String>) -> HashMap<String, Vec<String>> {
+fn parse_input(edges: Vec<(String, String)>) -> HashMap<String, Vec<String>> {
     let mut children = HashMap::new();
     children.insert("COM".to_string(), vec![]);
 
-    for line in input {
-        let mut s = line.split(")");
-        let a: String = s.next().unwrap().to_string();
-        let b: String = s.next().unwrap().to_string();
-        if !children.contains_key(&a) {
-            children.insert(a, vec![b]);
-        } else {
-            children.get_mut(&a).unwrap().push(b);
-        }
+    for (a, b) in edges {
+        children.entry(a).or_insert_with(Vec::new).push(b);
     }
 
     children
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day1");
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let s = fs::read_to_string(input_path).expect("Some input needs to exist");
-    let input = parse_input(s.lines().map(&str::to_string).collect());
-    let tree = build_tree(input);
-    let s1 = stage1(&tree);
-    log::info!("{:?}", s1);
-    let s2 = stage2(&tree);
-    log::info!("{:?}", s2);
-}
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    type Input = INPUT;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<INPUT, ParseError> {
+        Ok(build_tree(parse_input(parse::edges(input)?)))
+    }
+
+    fn part1(tree: &INPUT) -> usize {
+        stage1(tree)
+    }
+
+    fn part2(tree: &INPUT) -> usize {
+        stage2(tree)
+    }
 }
@@ -1,31 +1,28 @@
 use ndarray::{Array, Array1, Array2, Array3, Axis};
 
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
-
-use std::{
-    fs,
-    path::Path,
-    time::Instant,
+use crate::{
+    parse::{self, ParseError},
+    solution::Solution,
 };
 
-const DAY: u64 = 8;
-
 type INPUT = Array3<u8>;
 
-fn parse_image(input: &str, width: usize, height: usize) -> INPUT {
-    let input = input.trim();
-    let depth = input.len() / (width * height);
-    Array::from_shape_vec(
-        (depth, height, width),
-        input
-            .as_bytes()
-            .iter()
-            // .filter(|&b| *b as char != '\n')
-            .map(|&b| (b as char).to_digit(10).unwrap() as u8)
-            .collect::<Vec<u8>>(),
-    )
-    .unwrap()
+fn parse_image(input: &str, width: usize, height: usize) -> Result<INPUT, ParseError> {
+    let digits = parse::grid(input, |c| c.to_digit(10).map(|d| d as u8))?.concat();
+    let layer = width * height;
+    if digits.is_empty() || digits.len() % layer != 0 {
+        return Err(ParseError::new(
+            1,
+            digits.len() + 1,
+            format!(
+                "expected whole {}x{} layers, found {} digits",
+                width,
+                height,
+                digits.len()
+            ),
+        ));
+    }
+    Ok(Array::from_shape_vec((digits.len() / layer, height, width), digits).unwrap())
 }
 
 pub fn stage1(picture: &INPUT) -> usize {
@@ -47,7 +44,7 @@ pub fn stage1(picture: &INPUT) -> usize {
     layer.iter().filter(|&b| *b == 1).count() * layer.iter().filter(|&b| *b == 2).count()
 }
 
-pub fn stage2(picture: &INPUT) -> usize {
+pub fn stage2(picture: &INPUT) -> String {
     let layer = Array2::from_shape_vec(
         (25, 6),
         picture
@@ -65,37 +62,34 @@ pub fn stage2(picture: &INPUT) -> usize {
             .collect::<Vec<u8>>(),
     )
     .unwrap();
+    let mut image = String::new();
     layer.axis_iter(Axis(1)).for_each(|strip| {
+        image.push('\n');
         strip
             .iter()
-            .for_each(|&b| if b == 1 { print!("#") } else { print!(" ") });
-        println!();
+            .for_each(|&b| image.push(if b == 1 { '#' } else { ' ' }));
     });
-    0
+    image
 }
 
-pub fn run_day() {
-    #[cfg(feature = "profiler")]
-    profile_scope!("day8");
-    let start = Instant::now();
-    let input_path = Path::new("src")
-        .join(format!("day{:02}", DAY))
-        .join("input");
-    log::debug!("Opening file {:?}", input_path);
-    let input = fs::read_to_string(input_path).expect("Some input needs to exist");
-    let input = parse_image(&input, 25, 6);
-    // log::info!("Input: {:?}", input);
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = INPUT;
+    type Part1 = usize;
+    type Part2 = String;
 
-    log::debug!("Day 8 loading timer: {:?}", start.elapsed());
+    fn parse(input: &str) -> Result<INPUT, ParseError> {
+        parse_image(input, 25, 6)
+    }
 
-    // log::info!("{:?}", input);
-    let start = Instant::now();
-    let s1 = stage1(&input);
-    log::debug!("Stage 1 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s1);
+    fn part1(picture: &INPUT) -> usize {
+        stage1(picture)
+    }
 
-    let start = Instant::now();
-    let s2 = stage2(&input);
-    log::debug!("Stage 2 timer: {:?}", start.elapsed());
-    log::info!("{:?}", s2);
+    fn part2(picture: &INPUT) -> String {
+        stage2(picture)
+    }
 }
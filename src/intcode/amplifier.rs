@@ -0,0 +1,147 @@
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use std::collections::VecDeque;
+
+use super::{Interrupt, Program};
+
+/// How a row of amplifiers is wired together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Each amplifier feeds the next, the last one's output is the signal.
+    Chain,
+    /// Like `Chain`, but the last amplifier also feeds back into the first
+    /// until every amplifier halts.
+    Feedback,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Best {
+    pub phases: Vec<isize>,
+    pub signal: isize,
+}
+
+/// Tries every ordering of `phases` across `phases.len()` copies of
+/// `program`, spread over rayon's thread pool.
+///
+/// Ties are broken towards the lexicographically smallest phase ordering, so
+/// the answer doesn't depend on scheduling. Panics if `phases` is empty.
+pub fn search(program: &Program, phases: &[isize], topology: Topology) -> Best {
+    assert!(!phases.is_empty(), "Need at least one phase setting");
+    phases
+        .iter()
+        .cloned()
+        .permutations(phases.len())
+        .par_bridge()
+        .map(|phases| Best {
+            signal: signal(program, &phases, topology),
+            phases,
+        })
+        .max_by(|a, b| {
+            a.signal
+                .cmp(&b.signal)
+                .then_with(|| b.phases.cmp(&a.phases))
+        })
+        .expect("Permutations of a non-empty set are never empty")
+}
+
+/// The signal produced by a single phase ordering, starting from input 0.
+pub fn signal(program: &Program, phases: &[isize], topology: Topology) -> isize {
+    let count = phases.len();
+    let mut machines = vec![program.clone(); count];
+    let mut queues: Vec<VecDeque<isize>> = phases
+        .iter()
+        .map(|&phase| VecDeque::from(vec![phase]))
+        .collect();
+    let mut halted = vec![false; count];
+    let mut last = None;
+    queues[0].push_back(0);
+
+    while halted.iter().any(|h| !h) {
+        let mut progressed = false;
+        for i in 0..count {
+            if halted[i] {
+                continue;
+            }
+            loop {
+                match machines[i].run_until_interrupt() {
+                    Interrupt::Output(value) => {
+                        progressed = true;
+                        if i + 1 == count {
+                            last = Some(value);
+                            if topology == Topology::Chain {
+                                continue;
+                            }
+                        }
+                        queues[(i + 1) % count].push_back(value);
+                    }
+                    Interrupt::Input => match queues[i].pop_front() {
+                        Some(value) => {
+                            progressed = true;
+                            machines[i].provide_input(value);
+                        }
+                        None => break,
+                    },
+                    Interrupt::Halted => {
+                        progressed = true;
+                        halted[i] = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if !progressed {
+            panic!("Amplifiers are deadlocked waiting on input");
+        }
+    }
+
+    last.expect("Last amplifier never produced a signal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAIN: &str = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+    const FEEDBACK: &str =
+        "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+
+    #[test]
+    fn test_chain() {
+        let p: Program = CHAIN.parse().unwrap();
+        assert_eq!(
+            search(&p, &[0, 1, 2, 3, 4], Topology::Chain),
+            Best {
+                phases: vec![4, 3, 2, 1, 0],
+                signal: 43210
+            }
+        );
+    }
+
+    #[test]
+    fn test_feedback() {
+        let p: Program = FEEDBACK.parse().unwrap();
+        assert_eq!(
+            search(&p, &[5, 6, 7, 8, 9], Topology::Feedback),
+            Best {
+                phases: vec![9, 8, 7, 6, 5],
+                signal: 139629729
+            }
+        );
+    }
+
+    #[test]
+    fn test_more_amplifiers() {
+        let p: Program = CHAIN.parse().unwrap();
+        let best = search(&p, &[0, 1, 2, 3, 4, 5, 6], Topology::Chain);
+        assert_eq!(best.phases, vec![6, 5, 4, 3, 2, 1, 0]);
+        assert_eq!(best.signal, 6543210);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least one phase setting")]
+    fn test_no_phases() {
+        let p: Program = CHAIN.parse().unwrap();
+        search(&p, &[], Topology::Chain);
+    }
+}
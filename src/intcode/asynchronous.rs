@@ -0,0 +1,128 @@
+use futures::{Sink, SinkExt, Stream, StreamExt};
+
+use std::{error::Error, fmt};
+
+use super::{Interrupt, Program};
+
+/// Why `run` stopped before the program halted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError<E> {
+    /// The output sink refused a value.
+    Sink(E),
+    /// The input stream ended while the program was waiting on it. The
+    /// program is left at that `INPUT`, so it can be resumed.
+    InputClosed,
+}
+
+impl<E: fmt::Display> fmt::Display for RunError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Sink(e) => write!(f, "Couldn't send output: {}", e),
+            RunError::InputClosed => write!(f, "Input ended while the program wanted more"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for RunError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RunError::Sink(e) => Some(e),
+            RunError::InputClosed => None,
+        }
+    }
+}
+
+/// Runs `program` to completion, awaiting `input` whenever it hits an `INPUT`
+/// with no value ready and pushing every output into `output`.
+///
+/// Nothing here assumes a runtime, so any executor works; many machines can
+/// share one thread by joining their futures, e.g. with
+/// `futures::executor::block_on(futures::future::join_all(...))`.
+pub async fn run<S, K>(
+    program: &mut Program,
+    mut input: S,
+    mut output: K,
+) -> Result<(), RunError<K::Error>>
+where
+    S: Stream<Item = isize> + Unpin,
+    K: Sink<isize> + Unpin,
+{
+    loop {
+        match program.run_until_interrupt() {
+            Interrupt::Output(value) => output.send(value).await.map_err(RunError::Sink)?,
+            Interrupt::Input => match input.next().await {
+                Some(value) => program.provide_input(value),
+                None => return Err(RunError::InputClosed),
+            },
+            Interrupt::Halted => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::{channel::mpsc, executor::block_on, future::join_all};
+
+    #[test]
+    fn test_feedback_loop() {
+        let p: Program =
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
+                .parse()
+                .unwrap();
+        let phases = [9, 8, 7, 6, 5];
+        let (senders, mut receivers): (Vec<_>, Vec<_>) =
+            phases.iter().map(|_| mpsc::unbounded()).unzip();
+        for (tx, &phase) in senders.iter().zip(phases.iter()) {
+            tx.unbounded_send(phase).unwrap();
+        }
+        senders[0].unbounded_send(0).unwrap();
+
+        let mut machines = vec![p; phases.len()];
+        let futures = machines
+            .iter_mut()
+            .zip(receivers.iter_mut())
+            .enumerate()
+            .map(|(i, (m, rx))| run(m, rx, senders[(i + 1) % phases.len()].clone()));
+        for result in block_on(join_all(futures)) {
+            result.unwrap();
+        }
+
+        assert_eq!(receivers[0].try_recv().ok(), Some(139629729));
+    }
+
+    #[test]
+    fn test_many_machines() {
+        // Reads a value and writes it back incremented by one.
+        let p: Program = "3,9,1001,9,1,9,4,9,99,0".parse().unwrap();
+        let count = 300;
+        let (senders, mut receivers): (Vec<_>, Vec<_>) =
+            (0..=count).map(|_| mpsc::unbounded()).unzip();
+
+        let mut machines = vec![p; count];
+        let futures = machines
+            .iter_mut()
+            .zip(receivers.iter_mut())
+            .enumerate()
+            .map(|(i, (m, rx))| run(m, rx, senders[i + 1].clone()));
+        senders[0].unbounded_send(0).unwrap();
+        for result in block_on(join_all(futures)) {
+            result.unwrap();
+        }
+
+        assert_eq!(receivers[count].try_recv().ok(), Some(count as isize));
+    }
+
+    #[test]
+    fn test_input_closed() {
+        let mut p: Program = "3,9,1001,9,1,9,4,9,99,0".parse().unwrap();
+        let (tx, _rx) = mpsc::unbounded();
+        let result = block_on(run(&mut p, futures::stream::empty(), tx.clone()));
+        assert_eq!(result, Err(RunError::InputClosed));
+
+        let (tx, mut rx) = mpsc::unbounded();
+        block_on(run(&mut p, futures::stream::iter(vec![4]), tx)).unwrap();
+        assert_eq!(rx.try_recv().ok(), Some(5));
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::Program;
+
+/// A copy of a program's memory and instruction pointer at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub ip: usize,
+    pub memory: Vec<isize>,
+}
+
+impl Program {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            ip: self.ip,
+            memory: self.memory.to_vec(),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Table of every cell with its address, hex and decimal value, and the
+    /// instruction decoded at each instruction boundary.
+    pub fn dump(&self) -> MemoryDump<'_> {
+        MemoryDump {
+            snapshot: self,
+            start: 0,
+            end: self.memory.len(),
+            decode_from: 0,
+        }
+    }
+
+    /// Like `dump`, but limited to `radius` cells on either side of `ip`.
+    /// Decoding starts at `ip`, so cells before it are shown as raw data.
+    pub fn window(&self, radius: usize) -> MemoryDump<'_> {
+        MemoryDump {
+            snapshot: self,
+            start: self.ip.saturating_sub(radius),
+            end: (self.ip + radius + 1).min(self.memory.len()),
+            decode_from: self.ip,
+        }
+    }
+
+    pub fn diff(&self, other: &Snapshot) -> MemoryDiff {
+        MemoryDiff::new(&self.memory, &other.memory)
+    }
+}
+
+pub struct MemoryDump<'a> {
+    snapshot: &'a Snapshot,
+    start: usize,
+    end: usize,
+    decode_from: usize,
+}
+
+impl<'a> Display for MemoryDump<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let memory = &self.snapshot.memory;
+        writeln!(
+            f,
+            "   {:>6}  {:>18}  {:>12}  instruction",
+            "addr", "hex", "dec"
+        )?;
+        let mut next_instruction = self.decode_from;
+        for addr in self.start..self.end {
+            let marker = if addr == self.snapshot.ip { ">" } else { " " };
+            let mut decoded = None;
+            if addr == next_instruction {
+                next_instruction += 1;
+                if let Some((text, size)) = disassemble(memory, addr) {
+                    next_instruction = addr + size;
+                    decoded = Some(text);
+                }
+            }
+            writeln!(
+                f,
+                "{}  {:>6}  {:>18}  {:>12}  {}",
+                marker,
+                addr,
+                hex(memory[addr]),
+                memory[addr],
+                decoded.unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn hex(value: isize) -> String {
+    if value < 0 {
+        format!("-{:#x}", value.unsigned_abs())
+    } else {
+        format!("{:#x}", value)
+    }
+}
+
+/// Decodes the instruction at `addr` into readable text and its size.
+/// Position operands print as `[n]`, immediates as `#n`.
+pub fn disassemble(memory: &[isize], addr: usize) -> Option<(String, usize)> {
+    let word = *memory.get(addr)?;
+    if word < 0 {
+        return None;
+    }
+    let (name, arity) = match word % 100 {
+        1 => ("ADD", 3),
+        2 => ("MULT", 3),
+        3 => ("INPUT", 1),
+        4 => ("OUTPUT", 1),
+        5 => ("JNZ", 2),
+        6 => ("JZ", 2),
+        7 => ("LESS", 3),
+        8 => ("EQ", 3),
+        99 => ("HALT", 0),
+        _ => return None,
+    };
+    let mut operands = Vec::with_capacity(arity);
+    let mut modes = word / 100;
+    for offset in 1..=arity {
+        let value = *memory.get(addr + offset)?;
+        operands.push(match modes % 10 {
+            0 => format!("[{}]", value),
+            1 => format!("#{}", value),
+            _ => return None,
+        });
+        modes /= 10;
+    }
+    if modes != 0 {
+        return None;
+    }
+    let text = if operands.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", name, operands.join(", "))
+    };
+    Some((text, arity + 1))
+}
+
+/// The cells that differ between two memory images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryDiff {
+    pub changes: Vec<CellChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellChange {
+    pub addr: usize,
+    pub before: Option<isize>,
+    pub after: Option<isize>,
+}
+
+impl MemoryDiff {
+    pub fn new(before: &[isize], after: &[isize]) -> Self {
+        let changes = (0..before.len().max(after.len()))
+            .map(|addr| CellChange {
+                addr,
+                before: before.get(addr).cloned(),
+                after: after.get(addr).cloned(),
+            })
+            .filter(|c| c.before != c.after)
+            .collect();
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for MemoryDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let show = |v: Option<isize>| v.map_or("-".to_string(), |v| v.to_string());
+        writeln!(f, "{:>6}  {:>12}  {:>12}", "addr", "before", "after")?;
+        for c in &self.changes {
+            writeln!(
+                f,
+                "{:>6}  {:>12}  {:>12}",
+                c.addr,
+                show(c.before),
+                show(c.after)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Panics with a cell-by-cell diff instead of two giant vectors when the
+/// memory images don't match.
+#[track_caller]
+pub fn assert_memory_eq(actual: &[isize], expected: &[isize]) {
+    let diff = MemoryDiff::new(expected, actual);
+    if !diff.is_empty() {
+        panic!(
+            "memory differs in {} cell(s) (before = expected, after = actual):\n{}",
+            diff.changes.len(),
+            diff
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        let memory = vec![1002, 4, 3, 4, 33, 99];
+        assert_eq!(
+            disassemble(&memory, 0),
+            Some(("MULT [4], #3, [4]".to_string(), 4))
+        );
+        assert_eq!(disassemble(&memory, 4), None);
+        assert_eq!(disassemble(&memory, 5), Some(("HALT".to_string(), 1)));
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = MemoryDiff::new(&[1, 2, 3], &[1, 5, 3, 7]);
+        assert_eq!(
+            diff.changes,
+            vec![
+                CellChange {
+                    addr: 1,
+                    before: Some(2),
+                    after: Some(5)
+                },
+                CellChange {
+                    addr: 3,
+                    before: None,
+                    after: Some(7)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window() {
+        let mut p: Program = "1,0,0,0,99".parse().unwrap();
+        p.interpret();
+        let dump = p.snapshot().window(1).to_string();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].starts_with('>'));
+        assert!(lines[2].ends_with("HALT"));
+    }
+}
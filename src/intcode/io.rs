@@ -0,0 +1,152 @@
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Stdin, Stdout, Write},
+    sync::mpsc::{Receiver, Sender},
+};
+
+/// Where an intcode program reads its `INPUT` values from.
+///
+/// `outputs` holds every value the program has written so far during the
+/// current run, so a source can react to what the machine has said.
+pub trait InputSource {
+    fn next_input(&mut self, outputs: &[isize]) -> Option<isize>;
+}
+
+/// Where an intcode program writes its `OUTPUT` values to.
+pub trait OutputSink {
+    fn send_output(&mut self, value: isize);
+}
+
+impl<T: InputSource + ?Sized> InputSource for &mut T {
+    fn next_input(&mut self, outputs: &[isize]) -> Option<isize> {
+        (**self).next_input(outputs)
+    }
+}
+
+impl<T: OutputSink + ?Sized> OutputSink for &mut T {
+    fn send_output(&mut self, value: isize) {
+        (**self).send_output(value)
+    }
+}
+
+/// Adapter that feeds the values of any iterator to the program.
+#[derive(Debug, Clone)]
+pub struct IterInput<I>(pub I);
+
+impl<I> InputSource for IterInput<I>
+where
+    I: Iterator<Item = isize>,
+{
+    fn next_input(&mut self, _outputs: &[isize]) -> Option<isize> {
+        self.0.next()
+    }
+}
+
+pub fn from_iter<I>(input: I) -> IterInput<I::IntoIter>
+where
+    I: IntoIterator<Item = isize>,
+{
+    IterInput(input.into_iter())
+}
+
+/// Adapter that computes each input on demand from the outputs seen so far.
+#[derive(Debug, Clone)]
+pub struct FnInput<F>(pub F);
+
+impl<F> InputSource for FnInput<F>
+where
+    F: FnMut(&[isize]) -> Option<isize>,
+{
+    fn next_input(&mut self, outputs: &[isize]) -> Option<isize> {
+        (self.0)(outputs)
+    }
+}
+
+pub fn from_fn<F>(f: F) -> FnInput<F>
+where
+    F: FnMut(&[isize]) -> Option<isize>,
+{
+    FnInput(f)
+}
+
+impl InputSource for VecDeque<isize> {
+    fn next_input(&mut self, _outputs: &[isize]) -> Option<isize> {
+        self.pop_front()
+    }
+}
+
+/// Blocks until the sending side provides a value, or hangs up.
+impl InputSource for Receiver<isize> {
+    fn next_input(&mut self, _outputs: &[isize]) -> Option<isize> {
+        self.recv().ok()
+    }
+}
+
+/// Reads one integer per line, skipping blank lines and warning about any
+/// that aren't integers.
+impl InputSource for Stdin {
+    fn next_input(&mut self, _outputs: &[isize]) -> Option<isize> {
+        let mut lock = self.lock();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if lock.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            match trimmed.parse() {
+                Ok(value) => return Some(value),
+                Err(e) => log::warn!("Skipping stdin line {:?}: {}", trimmed, e),
+            }
+        }
+    }
+}
+
+/// Adapter that hands every output to a closure.
+#[derive(Debug, Clone)]
+pub struct FnOutput<F>(pub F);
+
+impl<F> OutputSink for FnOutput<F>
+where
+    F: FnMut(isize),
+{
+    fn send_output(&mut self, value: isize) {
+        (self.0)(value)
+    }
+}
+
+pub fn to_fn<F>(f: F) -> FnOutput<F>
+where
+    F: FnMut(isize),
+{
+    FnOutput(f)
+}
+
+impl OutputSink for Vec<isize> {
+    fn send_output(&mut self, value: isize) {
+        self.push(value);
+    }
+}
+
+impl OutputSink for VecDeque<isize> {
+    fn send_output(&mut self, value: isize) {
+        self.push_back(value);
+    }
+}
+
+/// Outputs are dropped once the receiving side hangs up, so a downstream
+/// machine that halts early doesn't bring this one down with it.
+impl OutputSink for Sender<isize> {
+    fn send_output(&mut self, value: isize) {
+        let _ = self.send(value);
+    }
+}
+
+impl OutputSink for Stdout {
+    fn send_output(&mut self, value: isize) {
+        writeln!(self, "{}", value).expect("Couldn't write to stdout");
+    }
+}
@@ -0,0 +1,106 @@
+use std::{fmt, sync::Arc};
+
+const PAGE_BITS: usize = 8;
+const PAGE_SIZE: usize = 1 << PAGE_BITS;
+const PAGE_MASK: usize = PAGE_SIZE - 1;
+
+/// Copy-on-write program memory.
+///
+/// Cloning only bumps a reference count; the first write to a page after a
+/// clone copies just that page, so every clone of a program keeps sharing the
+/// pristine image for the cells it never touches.
+#[derive(Clone)]
+pub struct Memory {
+    pages: Arc<Vec<Arc<Vec<isize>>>>,
+    len: usize,
+}
+
+impl Memory {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn get(&self, addr: usize) -> isize {
+        self.pages[addr >> PAGE_BITS][addr & PAGE_MASK]
+    }
+
+    /// The cell at `addr`, or `None` outside of memory.
+    #[inline]
+    pub fn try_get(&self, addr: usize) -> Option<isize> {
+        self.pages
+            .get(addr >> PAGE_BITS)?
+            .get(addr & PAGE_MASK)
+            .copied()
+    }
+
+    #[inline]
+    pub fn set(&mut self, addr: usize, value: isize) {
+        assert!(addr < self.len, "Write outside of memory at {}", addr);
+        let pages = Arc::make_mut(&mut self.pages);
+        Arc::make_mut(&mut pages[addr >> PAGE_BITS])[addr & PAGE_MASK] = value;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = isize> + '_ {
+        self.pages.iter().flat_map(|page| page.iter().cloned())
+    }
+
+    pub fn to_vec(&self) -> Vec<isize> {
+        self.iter().collect()
+    }
+}
+
+impl From<&[isize]> for Memory {
+    fn from(cells: &[isize]) -> Self {
+        Self {
+            pages: Arc::new(
+                cells
+                    .chunks(PAGE_SIZE)
+                    .map(|page| Arc::new(page.to_vec()))
+                    .collect(),
+            ),
+            len: cells.len(),
+        }
+    }
+}
+
+impl fmt::Debug for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_on_write() {
+        let cells: Vec<isize> = (0..1000).collect();
+        let original = Memory::from(&cells[..]);
+        let mut copy = original.clone();
+        assert!(Arc::ptr_eq(&original.pages, &copy.pages));
+
+        copy.set(300, -1);
+        assert_eq!(original.get(300), 300);
+        assert_eq!(copy.get(300), -1);
+        assert_eq!(copy.len(), 1000);
+
+        // Only the page that was written to got copied.
+        assert!(!Arc::ptr_eq(&original.pages[1], &copy.pages[1]));
+        assert!(Arc::ptr_eq(&original.pages[0], &copy.pages[0]));
+        assert!(Arc::ptr_eq(&original.pages[3], &copy.pages[3]));
+    }
+
+    #[test]
+    fn test_to_vec() {
+        let cells: Vec<isize> = (0..600).rev().collect();
+        assert_eq!(Memory::from(&cells[..]).to_vec(), cells);
+    }
+}
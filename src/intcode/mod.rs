@@ -1,7 +1,24 @@
-#[cfg(feature = "profiler")]
-use thread_profiler::profile_scope;
-
-use std::{fmt::Debug, str::FromStr};
+use std::{
+    convert::{TryFrom, TryInto},
+    error::Error,
+    fmt::{self, Debug},
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::parse::{self, ParseError};
+
+pub mod amplifier;
+pub mod asynchronous;
+mod inspect;
+mod io;
+mod memory;
+pub mod search;
+
+pub use inspect::{assert_memory_eq, disassemble, CellChange, MemoryDiff, MemoryDump, Snapshot};
+pub use io::{from_fn, from_iter, to_fn, FnInput, FnOutput, InputSource, IterInput, OutputSink};
+pub use memory::Memory;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Opcode {
@@ -23,12 +40,14 @@ pub enum ParameterMode {
     Immediate = 1,
 }
 
-impl From<usize> for ParameterMode {
-    fn from(i: usize) -> Self {
+impl TryFrom<usize> for ParameterMode {
+    type Error = Fault;
+
+    fn try_from(i: usize) -> Result<Self, Fault> {
         match i {
-            0 => Self::Position,
-            1 => Self::Immediate,
-            _ => panic!("Not implemented yet"),
+            0 => Ok(Self::Position),
+            1 => Ok(Self::Immediate),
+            _ => Err(Fault::Mode(i)),
         }
     }
 }
@@ -41,35 +60,43 @@ pub struct Operation {
 }
 
 impl Operation {
+    /// Decodes the instruction at `ip` without executing it.
     #[inline]
-    fn decode(p: &Program, ip: usize) -> Self {
-        let word = p.get(ip as isize) as usize;
+    pub fn decode(p: &Program, ip: usize) -> Self {
+        Self::try_decode(p, ip).unwrap_or_else(|fault| panic!("{}", fault))
+    }
+
+    /// Like `decode`, but reports a fault instead of panicking on it.
+    #[inline]
+    pub fn try_decode(p: &Program, ip: usize) -> Result<Self, Fault> {
+        let word = p.try_get(ip as isize)? as usize;
+        let arg = |n: usize| p.try_get((ip + n) as isize);
         let modes = (
-            (word / 100 % 10).into(),
-            (word / 1000 % 10).into(),
-            (word / 10000).into(),
+            (word / 100 % 10).try_into()?,
+            (word / 1000 % 10).try_into()?,
+            (word / 10000).try_into()?,
         );
         let opcode = match word % 100 {
-            1 => Opcode::ADD(p.get_offset(1), p.get_offset(2), p.get_offset(3)),
-            2 => Opcode::MULT(p.get_offset(1), p.get_offset(2), p.get_offset(3)),
-            3 => Opcode::INPUT(p.get_offset(1)),
-            4 => Opcode::OUTPUT(p.get_offset(1)),
-            5 => Opcode::JNZ(p.get_offset(1), p.get_offset(2)),
-            6 => Opcode::JZ(p.get_offset(1), p.get_offset(2)),
-            7 => Opcode::LESS(p.get_offset(1), p.get_offset(2), p.get_offset(3)),
-            8 => Opcode::EQ(p.get_offset(1), p.get_offset(2), p.get_offset(3)),
+            1 => Opcode::ADD(arg(1)?, arg(2)?, arg(3)?),
+            2 => Opcode::MULT(arg(1)?, arg(2)?, arg(3)?),
+            3 => Opcode::INPUT(arg(1)?),
+            4 => Opcode::OUTPUT(arg(1)?),
+            5 => Opcode::JNZ(arg(1)?, arg(2)?),
+            6 => Opcode::JZ(arg(1)?, arg(2)?),
+            7 => Opcode::LESS(arg(1)?, arg(2)?, arg(3)?),
+            8 => Opcode::EQ(arg(1)?, arg(2)?, arg(3)?),
             99 => Opcode::HALT,
             op => Opcode::ERROR(op as isize),
         };
-        Self { modes, opcode }
+        Ok(Self { modes, opcode })
     }
 
     #[inline]
-    #[allow(dead_code)]
+    #[cfg_attr(
+        feature = "trace-intcode",
+        tracing::instrument(level = "trace", skip_all)
+    )]
     fn size(&self) -> usize {
-        #[cfg(feature = "profiler")]
-        profile_scope!("size");
-
         match self.opcode {
             Opcode::ADD(_, _, _) => 4,
             Opcode::MULT(_, _, _) => 4,
@@ -85,6 +112,46 @@ impl Operation {
     }
 }
 
+/// Why a program stopped before halting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// An instruction read or wrote outside of memory.
+    OutOfBounds(isize),
+    /// An `ADD` or `MULT` overflowed.
+    Overflow,
+    /// `ip` ran past the end of memory without a `HALT`.
+    RanOffEnd,
+    /// No instruction has this opcode.
+    Opcode(isize),
+    /// A parameter mode other than position or immediate.
+    Mode(usize),
+    /// An `INPUT` instruction found no input left.
+    InputClosed,
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fault::OutOfBounds(addr) => write!(f, "Access outside of memory at {}", addr),
+            Fault::Overflow => write!(f, "Arithmetic overflow"),
+            Fault::RanOffEnd => write!(f, "Didn't halt before end of input"),
+            Fault::Opcode(op) => write!(f, "Error opcode {}", op),
+            Fault::Mode(mode) => write!(f, "Unknown parameter mode {}", mode),
+            Fault::InputClosed => write!(f, "Not enough input"),
+        }
+    }
+}
+
+impl Error for Fault {}
+
+/// Why `Program::run_until_interrupt` handed control back to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    Output(isize),
+    Input,
+    Halted,
+}
+
 #[derive(Debug, Clone)]
 pub enum RunningStatus {
     Running,
@@ -96,195 +163,342 @@ pub enum RunningStatus {
 pub struct Program {
     ip: usize,
     cycles: usize,
-    memory: Vec<isize>,
+    memory: Memory,
     status: RunningStatus,
 }
 
 impl Program {
-    pub fn new(input: &Vec<isize>) -> Self {
-        #[cfg(feature = "profiler")]
-        profile_scope!("new");
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new(input: &[isize]) -> Self {
         Self {
             ip: 0,
             cycles: 0,
-            memory: input.to_vec(),
+            memory: Memory::from(input),
             status: RunningStatus::Running,
         }
     }
 
+    /// Reads and parses the program in `path`. Parse errors come back as
+    /// `InvalidData`, naming the file, line and column.
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e: ProgramParseError| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            })
+    }
+
     #[inline]
     pub fn load_input(&mut self, noun: isize, verb: isize) {
         self.set(1, noun);
         self.set(2, verb);
     }
 
-    #[allow(dead_code)]
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn interpret_iter<T>(self, input: T) -> ProgramIter<T>
     where
         T: Iterator<Item = isize>,
     {
-        #[cfg(feature = "profiler")]
-        profile_scope!("interpret");
-
         ProgramIter { p: self, input }
     }
 
-    pub fn interpret_input(&mut self, mut input: impl Iterator<Item = isize>) -> Vec<isize> {
+    /// Runs to the end on the given inputs, returning every output. Panics
+    /// on a fault, like running out of input; `run` reports it instead.
+    pub fn interpret_input(&mut self, input: impl Iterator<Item = isize>) -> Vec<isize> {
         let mut vec = Vec::new();
-        loop {
-            let output = self.interpret_to_output(&mut input);
-            if output.is_none() {
-                break;
-            }
-            vec.push(output.unwrap());
+        if let Err(fault) = self.run(&mut IterInput(input), &mut vec) {
+            panic!("{}", fault);
         }
-
         vec
     }
 
+    /// Runs until the program halts, pulling inputs from `input` and pushing
+    /// every output into `output`. Stops at the first fault, including
+    /// `Fault::InputClosed` when `input` has nothing left for an `INPUT`.
+    ///
+    /// The input source is shown all outputs produced so far by this call,
+    /// which lets it decide its next move based on what the machine said.
+    pub fn run(
+        &mut self,
+        input: &mut impl InputSource,
+        output: &mut impl OutputSink,
+    ) -> Result<(), Fault> {
+        let mut seen = Vec::new();
+        while let Some(value) = self.interpret_to_output_seen(input, &seen)? {
+            output.send_output(value);
+            seen.push(value);
+        }
+        Ok(())
+    }
+
     pub fn interpret(&mut self) -> isize {
         self.interpret_input(vec![].into_iter());
         self.get(0)
     }
 
+    /// Like `interpret`, but reports a fault, including the program asking
+    /// for input, instead of panicking on it.
+    pub fn try_interpret(&mut self) -> Result<isize, Fault> {
+        loop {
+            match self.try_run_until_interrupt()? {
+                Interrupt::Output(_) => {}
+                Interrupt::Input => return Err(Fault::InputClosed),
+                Interrupt::Halted => return self.try_get(0),
+            }
+        }
+    }
+
     #[inline]
-    fn set_mode(&mut self, mode: ParameterMode, ip: isize, value: isize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!("set");
+    #[cfg_attr(
+        feature = "trace-intcode",
+        tracing::instrument(level = "trace", skip_all)
+    )]
+    fn set_mode(&mut self, mode: ParameterMode, ip: isize, value: isize) -> Result<(), Fault> {
         match mode {
-            ParameterMode::Immediate => self.set(ip, value),
-            ParameterMode::Position => self.set(ip, value),
-        };
+            ParameterMode::Immediate => self.try_set(ip, value),
+            ParameterMode::Position => self.try_set(ip, value),
+        }
     }
 
     #[inline]
+    #[cfg_attr(
+        feature = "trace-intcode",
+        tracing::instrument(level = "trace", skip_all)
+    )]
     fn set(&mut self, ip: isize, value: isize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!("set");
-        self.memory[ip as usize] = value;
+        self.memory.set(ip as usize, value);
     }
 
     #[inline]
     #[allow(dead_code)]
     fn set_indirect(&mut self, ip: isize, value: isize) {
-        let index = self.memory[self.get(ip) as usize] as usize;
-        self.memory[index] = value;
+        let index = self.memory.get(self.get(ip) as usize) as usize;
+        self.memory.set(index, value);
     }
 
     #[inline]
-    fn get(&self, ip: isize) -> isize {
-        self.memory[ip as usize]
+    fn try_set(&mut self, ip: isize, value: isize) -> Result<(), Fault> {
+        match usize::try_from(ip) {
+            Ok(addr) if addr < self.memory.len() => {
+                self.memory.set(addr, value);
+                Ok(())
+            }
+            _ => Err(Fault::OutOfBounds(ip)),
+        }
     }
 
-    fn get_mode(&self, mode: ParameterMode, ip: isize) -> isize {
-        match mode {
-            ParameterMode::Immediate => ip,
-            ParameterMode::Position => self.get(ip),
-        }
+    #[inline]
+    fn get(&self, ip: isize) -> isize {
+        self.memory.get(ip as usize)
     }
 
     #[inline]
-    fn get_offset(&self, offset: isize) -> isize {
-        self.memory[(self.ip as isize + offset) as usize]
+    fn try_get(&self, ip: isize) -> Result<isize, Fault> {
+        usize::try_from(ip)
+            .ok()
+            .and_then(|addr| self.memory.try_get(addr))
+            .ok_or(Fault::OutOfBounds(ip))
+    }
+
+    fn get_mode(&self, mode: ParameterMode, ip: isize) -> Result<isize, Fault> {
+        match mode {
+            ParameterMode::Immediate => Ok(ip),
+            ParameterMode::Position => self.try_get(ip),
+        }
     }
 
     #[inline]
+    #[cfg_attr(
+        feature = "trace-intcode",
+        tracing::instrument(level = "trace", skip_all)
+    )]
     fn advance(&mut self, op: Operation) {
-        #[cfg(feature = "profiler")]
-        profile_scope!("advance");
         self.cycles += 1;
         self.ip += op.size();
     }
 
-    pub fn interpret_to_output<T>(&mut self, input: &mut T) -> Option<T::Item>
-    where
-        T: Iterator<Item = isize>,
-    {
+    /// Runs until the next output, or `None` once the program halts. Stops
+    /// at a fault like `run` does.
+    pub fn interpret_to_output(
+        &mut self,
+        input: &mut impl InputSource,
+    ) -> Result<Option<isize>, Fault> {
+        self.interpret_to_output_seen(input, &[])
+    }
+
+    fn interpret_to_output_seen(
+        &mut self,
+        input: &mut impl InputSource,
+        seen: &[isize],
+    ) -> Result<Option<isize>, Fault> {
+        loop {
+            match self.try_run_until_interrupt()? {
+                Interrupt::Output(value) => return Ok(Some(value)),
+                Interrupt::Input => match input.next_input(seen) {
+                    Some(value) => self.provide_input(value),
+                    None => return Err(Fault::InputClosed),
+                },
+                Interrupt::Halted => return Ok(None),
+            }
+        }
+    }
+
+    /// Feeds `value` to the `INPUT` instruction that the last call to
+    /// `run_until_interrupt` stopped at.
+    pub fn provide_input(&mut self, value: isize) {
+        let op = Operation::decode(self, self.ip);
+        match op.opcode {
+            Opcode::INPUT(i1) => {
+                self.set(i1, value);
+                self.advance(op);
+            }
+            opcode => panic!("Expected to be waiting on INPUT, found {:?}", opcode),
+        }
+    }
+
+    /// Executes until the program produces an output, needs an input, or
+    /// halts. An `INPUT` instruction is left pending until `provide_input`.
+    pub fn run_until_interrupt(&mut self) -> Interrupt {
+        self.try_run_until_interrupt()
+            .unwrap_or_else(|fault| panic!("{}", fault))
+    }
+
+    /// Like `run_until_interrupt`, but reports a fault instead of panicking
+    /// on it.
+    pub fn try_run_until_interrupt(&mut self) -> Result<Interrupt, Fault> {
         loop {
             let mut jumped = false;
             if self.ip >= self.memory.len() {
-                panic!("Didn't halt before end of input");
+                return Err(Fault::RanOffEnd);
             }
 
-            let op = Operation::decode(self, self.ip);
+            let op = Operation::try_decode(self, self.ip)?;
             match op.opcode {
                 Opcode::ADD(s1, s2, d) => {
                     // println!(
                     //     "ADD {:?}:{}, {:?}:{} -> {:?}:{}",
                     //     op.modes.0, s1, op.modes.1, s2, op.modes.2, d
                     // );
-                    let a = self.get_mode(op.modes.0, s1);
-                    let b = self.get_mode(op.modes.1, s2);
-                    self.set_mode(op.modes.2, d, a + b);
+                    let a = self.get_mode(op.modes.0, s1)?;
+                    let b = self.get_mode(op.modes.1, s2)?;
+                    let sum = a.checked_add(b).ok_or(Fault::Overflow)?;
+                    self.set_mode(op.modes.2, d, sum)?;
                 }
                 Opcode::MULT(s1, s2, d) => {
                     // println!(
                     //     "MULT {:?}:{}, {:?}:{} -> {:?}:{}",
                     //     op.modes.0, s1, op.modes.1, s2, op.modes.2, d
                     // );
-                    let a = self.get_mode(op.modes.0, s1);
-                    let b = self.get_mode(op.modes.1, s2);
-                    self.set_mode(op.modes.2, d, a * b);
+                    let a = self.get_mode(op.modes.0, s1)?;
+                    let b = self.get_mode(op.modes.1, s2)?;
+                    let product = a.checked_mul(b).ok_or(Fault::Overflow)?;
+                    self.set_mode(op.modes.2, d, product)?;
                 }
-                Opcode::INPUT(i1) => {
+                Opcode::INPUT(_) => {
                     // println!("INPUT {:?}:{}", op.modes.0, i1);
-                    self.set(i1, input.next().expect("Not enough input"));
+                    return Ok(Interrupt::Input);
                 }
                 Opcode::OUTPUT(o1) => {
                     // println!("OUTPUT {:?}:{}", op.modes.0, o1);
+                    let value = self.get_mode(op.modes.0, o1)?;
                     self.advance(op);
-                    return Some(self.get(o1));
+                    return Ok(Interrupt::Output(value));
                 }
                 Opcode::JNZ(test, dest) => {
                     // println!("JNZ {:?}:{} {:?}:{}", op.modes.0, test, op.modes.1, dest);
-                    if self.get_mode(op.modes.0, test) != 0 {
-                        self.ip = self.get_mode(op.modes.1, dest) as usize;
+                    if self.get_mode(op.modes.0, test)? != 0 {
+                        self.ip = self.get_mode(op.modes.1, dest)? as usize;
                         jumped = true;
                     }
                 }
                 Opcode::JZ(test, dest) => {
-                    if self.get_mode(op.modes.0, test) == 0 {
-                        self.ip = self.get_mode(op.modes.1, dest) as usize;
+                    if self.get_mode(op.modes.0, test)? == 0 {
+                        self.ip = self.get_mode(op.modes.1, dest)? as usize;
                         jumped = true;
                     }
                 }
                 Opcode::LESS(s1, s2, dest) => {
-                    let a = self.get_mode(op.modes.0, s1);
-                    let b = self.get_mode(op.modes.1, s2);
-                    self.set_mode(op.modes.2, dest, if a < b { 1 } else { 0 });
+                    let a = self.get_mode(op.modes.0, s1)?;
+                    let b = self.get_mode(op.modes.1, s2)?;
+                    self.set_mode(op.modes.2, dest, if a < b { 1 } else { 0 })?;
                 }
                 Opcode::EQ(s1, s2, dest) => {
-                    let a = self.get_mode(op.modes.0, s1);
-                    let b = self.get_mode(op.modes.1, s2);
-                    self.set_mode(op.modes.2, dest, if a == b { 1 } else { 0 });
+                    let a = self.get_mode(op.modes.0, s1)?;
+                    let b = self.get_mode(op.modes.1, s2)?;
+                    self.set_mode(op.modes.2, dest, if a == b { 1 } else { 0 })?;
                 }
                 Opcode::HALT => {
                     self.status = RunningStatus::Halted;
-                    break;
+                    return Ok(Interrupt::Halted);
                 }
                 Opcode::ERROR(op) => {
                     self.status = RunningStatus::Killed;
-                    panic!("Error opcode {}", op);
+                    return Err(Fault::Opcode(op));
                 }
             }
             if !jumped {
                 self.advance(op);
             }
         }
-        None
+    }
+}
+
+/// Where and why the text of an intcode program didn't parse. Lines and
+/// columns count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ProgramParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ProgramParseError {}
+
+impl From<ParseError> for ProgramParseError {
+    fn from(e: ParseError) -> Self {
+        Self {
+            line: e.line,
+            column: e.column,
+            message: e.message,
+        }
+    }
+}
+
+impl From<ProgramParseError> for ParseError {
+    fn from(e: ProgramParseError) -> Self {
+        ParseError::new(e.line, e.column, e.message)
     }
 }
 
 impl FromStr for Program {
-    type Err = std::num::ParseIntError;
+    type Err = ProgramParseError;
 
+    /// Parses comma separated integers, allowing whitespace around them, a
+    /// trailing comma and blank lines.
     fn from_str(input: &str) -> Result<Program, Self::Err> {
-        let v = input
-            .lines()
-            .map(|s| s.split(',').map(isize::from_str))
-            .flatten()
-            .collect::<Result<Vec<isize>, Self::Err>>();
-        Ok(Program::new(&v.unwrap()))
+        let cells: Vec<isize> = parse::separated(input, ',')?;
+        if cells.is_empty() {
+            return Err(ProgramParseError {
+                line: 1,
+                column: 1,
+                message: "expected at least one value".to_string(),
+            });
+        }
+        Ok(Program::new(&cells))
     }
 }
 
@@ -294,13 +508,16 @@ pub struct ProgramIter<A> {
     input: A,
 }
 
+/// Panics on a fault, like `Program::interpret_input`.
 impl<A> Iterator for ProgramIter<A>
 where
     A: Iterator<Item = isize>,
 {
     type Item = isize;
     fn next(&mut self) -> Option<isize> {
-        self.p.interpret_to_output(&mut self.input)
+        self.p
+            .interpret_to_output(&mut IterInput(&mut self.input))
+            .unwrap_or_else(|fault| panic!("{}", fault))
     }
 }
 
@@ -310,48 +527,132 @@ mod tests {
 
     fn run(test: &str, input: Vec<isize>) -> (Vec<isize>, Vec<isize>) {
         let mut p: Program = test.parse().unwrap();
-        let (_, output) = p.interpret_input(input.into_iter());
-        (p.memory, output)
+        let output = p.interpret_input(input.into_iter());
+        (p.snapshot().memory, output)
     }
 
     #[test]
     fn test_simple() {
-        assert_eq!(run("1,0,0,0,99", vec![]).0, vec![2, 0, 0, 0, 99]);
-        assert_eq!(run("2,3,0,3,99", vec![]).0, vec![2, 3, 0, 6, 99]);
+        assert_memory_eq(&run("1,0,0,0,99", vec![]).0, &[2, 0, 0, 0, 99]);
+        assert_memory_eq(&run("2,3,0,3,99", vec![]).0, &[2, 3, 0, 6, 99]);
+    }
+
+    #[test]
+    fn test_decode_elsewhere() {
+        let p: Program = "1,0,0,0,1002,4,3,4,99".parse().unwrap();
+        let op = Operation::decode(&p, 4);
+        assert_eq!(op.opcode, Opcode::MULT(4, 3, 4));
+        assert_eq!(op.size(), 4);
+        assert_eq!(Operation::decode(&p, 8).opcode, Opcode::HALT);
+    }
+
+    #[test]
+    fn test_parse_quirks() {
+        let p: Program = " 1, 0,0 ,0,\n99,\n\n".parse().unwrap();
+        assert_memory_eq(&p.snapshot().memory, &[1, 0, 0, 0, 99]);
+
+        let e = "1,0\n0,x,99".parse::<Program>().unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 3: expected a number, found \"x\""
+        );
+        assert!("\n\n".parse::<Program>().is_err());
+        assert!("1,,2".parse::<Program>().is_err());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let p = Program::from_file("src/day02/input").unwrap();
+        assert_eq!(p.snapshot().memory[0], 1);
+        let e = Program::from_file("src/day02/missing").unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
     }
 
     #[test]
     fn test_modes() {
-        assert_eq!(run("1101,100,-1,4,0", vec![]).0, vec![1101, 100, -1, 4, 99]);
-        assert_eq!(run("1002,4,3,4,33", vec![]).0, vec![1002, 4, 3, 4, 99]);
+        assert_memory_eq(&run("1101,100,-1,4,0", vec![]).0, &[1101, 100, -1, 4, 99]);
+        assert_memory_eq(&run("1002,4,3,4,33", vec![]).0, &[1002, 4, 3, 4, 99]);
+        assert_eq!(run("104,42,4,0,99", vec![]).1, vec![42, 104]);
     }
 
     #[test]
     fn test_io() {
         let (mem, output) = run("3,9,8,9,10,9,4,9,99,-1,8", vec![9]);
-        assert_eq!(mem, vec![3, 9, 8, 9, 10, 9, 4, 9, 99, 0, 8]);
+        assert_memory_eq(&mem, &[3, 9, 8, 9, 10, 9, 4, 9, 99, 0, 8]);
         assert_eq!(output, vec![0]);
         let (mem, output) = run("3,9,8,9,10,9,4,9,99,-1,8", vec![8]);
-        assert_eq!(mem, vec![3, 9, 8, 9, 10, 9, 4, 9, 99, 1, 8]);
+        assert_memory_eq(&mem, &[3, 9, 8, 9, 10, 9, 4, 9, 99, 1, 8]);
         assert_eq!(output, vec![1]);
         let (mem, output) = run("3,9,7,9,10,9,4,9,99,-1,8", vec![8]);
-        assert_eq!(mem, vec![3, 9, 7, 9, 10, 9, 4, 9, 99, 0, 8]);
+        assert_memory_eq(&mem, &[3, 9, 7, 9, 10, 9, 4, 9, 99, 0, 8]);
         assert_eq!(output, vec![0]);
         let (mem, output) = run("3,9,7,9,10,9,4,9,99,-1,8", vec![1]);
-        assert_eq!(mem, vec![3, 9, 7, 9, 10, 9, 4, 9, 99, 1, 8]);
+        assert_memory_eq(&mem, &[3, 9, 7, 9, 10, 9, 4, 9, 99, 1, 8]);
         assert_eq!(output, vec![1]);
     }
 
     #[test]
     fn test_jumps() {
         let (mem, output) = run("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", vec![9]);
-        assert_eq!(mem, vec![3, 3, 1105, 9, 9, 1101, 0, 0, 12, 4, 12, 99, 1]);
+        assert_memory_eq(&mem, &[3, 3, 1105, 9, 9, 1101, 0, 0, 12, 4, 12, 99, 1]);
         assert_eq!(output, vec![1]);
         let (mem, output) = run("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9", vec![0]);
-        assert_eq!(
-            mem,
-            vec![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, 0, 0, 1, 9]
+        assert_memory_eq(
+            &mem,
+            &[3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, 0, 0, 1, 9],
         );
         assert_eq!(output, vec![0]);
     }
+
+    #[test]
+    fn test_input_sources() {
+        use std::{collections::VecDeque, sync::mpsc::channel};
+
+        let program: Program = "3,9,8,9,10,9,4,9,99,-1,8".parse().unwrap();
+
+        let mut output = Vec::new();
+        program
+            .clone()
+            .run(&mut VecDeque::from(vec![8]), &mut output)
+            .unwrap();
+        assert_eq!(output, vec![1]);
+
+        let (tx, mut rx) = channel();
+        tx.send(9).unwrap();
+        let mut output = VecDeque::new();
+        program.clone().run(&mut rx, &mut output).unwrap();
+        assert_eq!(output, VecDeque::from(vec![0]));
+
+        // Nothing left to read is a fault, not a panic.
+        let mut output = Vec::new();
+        assert_eq!(
+            program
+                .clone()
+                .run(&mut VecDeque::<isize>::new(), &mut output),
+            Err(Fault::InputClosed)
+        );
+        assert_eq!(
+            program
+                .clone()
+                .interpret_to_output(&mut VecDeque::<isize>::new()),
+            Err(Fault::InputClosed)
+        );
+    }
+
+    #[test]
+    fn test_input_from_outputs() {
+        // Echoes each input back until it reads a zero.
+        let mut p: Program = "3,11,4,11,1005,11,0,99,0,0,0,0".parse().unwrap();
+        let mut output = Vec::new();
+        p.run(
+            &mut from_fn(|seen: &[isize]| match seen.last() {
+                None => Some(3),
+                Some(last) => Some(last - 1),
+            }),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(output, vec![3, 2, 1, 0]);
+    }
 }
@@ -0,0 +1,541 @@
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use std::{collections::HashMap, convert::TryFrom, ops::RangeInclusive};
+
+use super::{Fault, Opcode, Operation, ParameterMode, Program};
+
+/// Programs that take longer than this many steps to halt aren't worth
+/// modelling; they're searched by brute force instead.
+const MAX_STEPS: usize = 1 << 20;
+
+/// An address to overwrite before running, and the values to try there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub addr: usize,
+    pub domain: RangeInclusive<isize>,
+}
+
+/// What to look for: every combination of patched values that leaves
+/// `target` at `output` once the program halts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub patches: Vec<Patch>,
+    pub output: usize,
+    pub target: isize,
+}
+
+impl Query {
+    /// The day 2 setup: noun and verb at addresses 1 and 2, both `0..=99`,
+    /// with the answer left at address 0.
+    pub fn noun_verb(target: isize) -> Self {
+        Query {
+            patches: vec![
+                Patch {
+                    addr: 1,
+                    domain: 0..=99,
+                },
+                Patch {
+                    addr: 2,
+                    domain: 0..=99,
+                },
+            ],
+            output: 0,
+            target,
+        }
+    }
+
+    /// Runs a copy of `program` with `values` patched in, returning what's
+    /// left at the output address, or the fault that stopped it.
+    pub fn run(&self, program: &Program, values: &[isize]) -> Result<isize, Fault> {
+        let mut p = program.clone();
+        for (patch, &value) in self.patches.iter().zip(values) {
+            p.try_set(patch.addr as isize, value)?;
+        }
+        p.try_interpret()?;
+        p.try_get(self.output as isize)
+    }
+
+    /// Whether `values` leave the target at the output. Values the program
+    /// faults on don't.
+    fn solves(&self, program: &Program, values: &[isize]) -> bool {
+        self.run(program, values) == Ok(self.target)
+    }
+}
+
+/// Every combination of values from `patches`' domains. No patches at all
+/// make a single empty combination.
+fn combinations(patches: &[Patch]) -> Box<dyn Iterator<Item = Vec<isize>> + Send> {
+    if patches.is_empty() {
+        Box::new(std::iter::once(vec![]))
+    } else {
+        Box::new(
+            patches
+                .iter()
+                .map(|p| p.domain.clone())
+                .multi_cartesian_product(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Solved from a linear model of the output, worked out by running the
+    /// program on every combination at once and confirmed for every solution
+    /// with a real run.
+    Linear,
+    /// Ran every combination, spread over rayon's thread pool.
+    BruteForce,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub strategy: Strategy,
+    /// Patched values in the order of `Query::patches`, sorted.
+    pub solutions: Vec<Vec<isize>>,
+}
+
+/// A value as `constant + sum(coefficient * value)` over the patched values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Model {
+    constant: i128,
+    coefficients: Vec<i128>,
+}
+
+impl Model {
+    fn known(constant: i128, patches: usize) -> Self {
+        Model {
+            constant,
+            coefficients: vec![0; patches],
+        }
+    }
+
+    /// Just the value of patch `i`.
+    fn patch(i: usize, patches: usize) -> Self {
+        let mut model = Model::known(0, patches);
+        model.coefficients[i] = 1;
+        model
+    }
+
+    /// The value, if it's the same for every combination.
+    fn constant(&self) -> Option<i128> {
+        if self.coefficients.iter().all(|&c| c == 0) {
+            Some(self.constant)
+        } else {
+            None
+        }
+    }
+
+    fn add(&self, other: &Model) -> Option<Model> {
+        Some(Model {
+            constant: self.constant.checked_add(other.constant)?,
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(&other.coefficients)
+                .map(|(a, b)| a.checked_add(*b))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    fn scale(&self, factor: i128) -> Option<Model> {
+        Some(Model {
+            constant: self.constant.checked_mul(factor)?,
+            coefficients: self
+                .coefficients
+                .iter()
+                .map(|c| c.checked_mul(factor))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// The smallest and largest value over every combination in `patches`.
+    fn bounds(&self, patches: &[Patch]) -> Option<(i128, i128)> {
+        let (mut low, mut high) = (self.constant, self.constant);
+        for (&c, patch) in self.coefficients.iter().zip(patches) {
+            let a = c.checked_mul(*patch.domain.start() as i128)?;
+            let b = c.checked_mul(*patch.domain.end() as i128)?;
+            low = low.checked_add(a.min(b))?;
+            high = high.checked_add(a.max(b))?;
+        }
+        Some((low, high))
+    }
+
+    fn predict(&self, values: &[isize]) -> i128 {
+        self.constant
+            + self
+                .coefficients
+                .iter()
+                .zip(values)
+                .map(|(c, &v)| c * v as i128)
+                .sum::<i128>()
+    }
+}
+
+/// A memory cell while modelling: linear in the patched values, or anything
+/// else, like the product of two of them.
+#[derive(Debug, Clone)]
+enum Cell {
+    Linear(Model),
+    Unknown,
+}
+
+impl Cell {
+    fn constant(&self) -> Option<i128> {
+        match self {
+            Cell::Linear(model) => model.constant(),
+            Cell::Unknown => None,
+        }
+    }
+}
+
+/// A program running on every combination of patched values at once. The
+/// cells that depend on them live in `cells`, everything else in `p`.
+struct Symbolic<'a> {
+    p: Program,
+    cells: HashMap<usize, Cell>,
+    patches: &'a [Patch],
+}
+
+impl<'a> Symbolic<'a> {
+    fn cell(&self, addr: usize) -> Option<Cell> {
+        match self.cells.get(&addr) {
+            Some(cell) => Some(cell.clone()),
+            None => {
+                let value = self.p.memory.try_get(addr)?;
+                Some(Cell::Linear(Model::known(
+                    value as i128,
+                    self.patches.len(),
+                )))
+            }
+        }
+    }
+
+    fn addr(&self, cell: &Cell) -> Option<usize> {
+        usize::try_from(cell.constant()?)
+            .ok()
+            .filter(|&addr| addr < self.p.memory.len())
+    }
+
+    /// Reads a parameter. Reading through an address that depends on the
+    /// patched values gives an unknown cell, as long as it's in memory for
+    /// every combination.
+    fn get(&self, mode: ParameterMode, param: &Cell) -> Option<Cell> {
+        match mode {
+            ParameterMode::Immediate => Some(param.clone()),
+            ParameterMode::Position => match param {
+                Cell::Linear(model) if model.constant().is_some() => self.cell(self.addr(param)?),
+                Cell::Linear(model) => {
+                    let (low, high) = model.bounds(self.patches)?;
+                    if low >= 0 && high < self.p.memory.len() as i128 {
+                        Some(Cell::Unknown)
+                    } else {
+                        None
+                    }
+                }
+                Cell::Unknown => None,
+            },
+        }
+    }
+
+    fn set(&mut self, param: &Cell, value: Cell) -> Option<()> {
+        let addr = self.addr(param)?;
+        match value.constant().and_then(|v| isize::try_from(v).ok()) {
+            Some(value) => {
+                self.cells.remove(&addr);
+                self.p.memory.set(addr, value);
+            }
+            None => {
+                self.cells.insert(addr, value);
+            }
+        }
+        Some(())
+    }
+}
+
+fn add(a: Cell, b: Cell) -> Cell {
+    match (a, b) {
+        (Cell::Linear(a), Cell::Linear(b)) => a.add(&b).map_or(Cell::Unknown, Cell::Linear),
+        _ => Cell::Unknown,
+    }
+}
+
+fn mult(a: Cell, b: Cell) -> Cell {
+    match (a.constant(), b.constant(), a, b) {
+        (Some(factor), _, _, Cell::Linear(model)) | (_, Some(factor), Cell::Linear(model), _) => {
+            model.scale(factor).map_or(Cell::Unknown, Cell::Linear)
+        }
+        _ => Cell::Unknown,
+    }
+}
+
+fn compare(a: Cell, b: Cell, patches: usize, op: impl Fn(i128, i128) -> bool) -> Cell {
+    match (a.constant(), b.constant()) {
+        (Some(a), Some(b)) => Cell::Linear(Model::known(op(a, b) as i128, patches)),
+        _ => Cell::Unknown,
+    }
+}
+
+/// Works out the output as a linear model of the patched values by running
+/// the program on all of them at once. Gives up with `None` when anything
+/// the run can't be sure of would matter: a jump, an instruction or a
+/// written address that depends on the values, an output that isn't linear
+/// in them, a read that might fault, input, or simply running too long.
+///
+/// Whatever comes back is exact for every combination the program doesn't
+/// fault on, since they all take the same path.
+fn model(program: &Program, query: &Query) -> Option<Model> {
+    let n = query.patches.len();
+    let mut s = Symbolic {
+        p: program.clone(),
+        cells: HashMap::new(),
+        patches: &query.patches,
+    };
+    for (i, patch) in query.patches.iter().enumerate() {
+        if patch.addr >= s.p.memory.len() {
+            return None;
+        }
+        s.cells.insert(patch.addr, Cell::Linear(Model::patch(i, n)));
+    }
+
+    for _ in 0..MAX_STEPS {
+        let ip = s.p.ip;
+        if s.cells.contains_key(&ip) {
+            return None;
+        }
+        let op = Operation::try_decode(&s.p, ip).ok()?;
+        let params = (1..op.size())
+            .map(|i| s.cell(ip + i))
+            .collect::<Option<Vec<_>>>()?;
+        let (m0, m1, _) = op.modes;
+        let mut next = ip + op.size();
+        match op.opcode {
+            Opcode::ADD(..) => {
+                let sum = add(s.get(m0, &params[0])?, s.get(m1, &params[1])?);
+                s.set(&params[2], sum)?;
+            }
+            Opcode::MULT(..) => {
+                let product = mult(s.get(m0, &params[0])?, s.get(m1, &params[1])?);
+                s.set(&params[2], product)?;
+            }
+            Opcode::LESS(..) => {
+                let less = compare(s.get(m0, &params[0])?, s.get(m1, &params[1])?, n, |a, b| {
+                    a < b
+                });
+                s.set(&params[2], less)?;
+            }
+            Opcode::EQ(..) => {
+                let eq = compare(s.get(m0, &params[0])?, s.get(m1, &params[1])?, n, |a, b| {
+                    a == b
+                });
+                s.set(&params[2], eq)?;
+            }
+            Opcode::JNZ(..) | Opcode::JZ(..) => {
+                let test = s.get(m0, &params[0])?.constant()?;
+                if (test != 0) == matches!(op.opcode, Opcode::JNZ(..)) {
+                    next = usize::try_from(s.get(m1, &params[1])?.constant()?).ok()?;
+                }
+            }
+            Opcode::OUTPUT(..) => {
+                s.get(m0, &params[0])?;
+            }
+            Opcode::HALT => {
+                return match s.cell(query.output)? {
+                    Cell::Linear(model) => Some(model),
+                    Cell::Unknown => None,
+                };
+            }
+            Opcode::INPUT(..) | Opcode::ERROR(..) => return None,
+        }
+        s.p.ip = next;
+    }
+    log::debug!("Gave up modelling after {} steps", MAX_STEPS);
+    None
+}
+
+/// Solves `query` with a linear model of the output, or `None` when the
+/// output can't be shown to be linear in the patched values. Every solution
+/// the model proposes is confirmed with a real run.
+pub fn linear(program: &Program, query: &Query) -> Option<Vec<Vec<isize>>> {
+    let (last, rest) = match query.patches.split_last() {
+        Some(split) => split,
+        None => {
+            return Some(if query.solves(program, &[]) {
+                vec![vec![]]
+            } else {
+                vec![]
+            })
+        }
+    };
+    let model = model(program, query)?;
+    let last_coefficient = model.coefficients[rest.len()];
+
+    let mut solutions = Vec::new();
+    for prefix in combinations(rest) {
+        let mut values = prefix;
+        values.push(0);
+        let remainder = query.target as i128 - model.predict(&values);
+        values.pop();
+        let candidates: Vec<isize> = if last_coefficient == 0 {
+            if remainder == 0 {
+                last.domain.clone().collect()
+            } else {
+                vec![]
+            }
+        } else if remainder % last_coefficient == 0 {
+            let value = remainder / last_coefficient;
+            if value >= *last.domain.start() as i128 && value <= *last.domain.end() as i128 {
+                vec![value as isize]
+            } else {
+                vec![]
+            }
+        } else {
+            vec![]
+        };
+        for value in candidates {
+            let mut solution = values.clone();
+            solution.push(value);
+            // Only a fault, like an overflow the model doesn't know about,
+            // gets here.
+            if !query.solves(program, &solution) {
+                log::debug!("Linear model was wrong about {:?}", solution);
+                return None;
+            }
+            solutions.push(solution);
+        }
+    }
+    Some(solutions)
+}
+
+/// Runs every combination of patched values, spread over rayon's thread
+/// pool.
+pub fn brute_force(program: &Program, query: &Query) -> Vec<Vec<isize>> {
+    let mut solutions: Vec<Vec<isize>> = combinations(&query.patches)
+        .par_bridge()
+        .filter(|values| query.solves(program, values))
+        .collect();
+    solutions.sort();
+    solutions
+}
+
+/// Every solution to `query`, found linearly when the output is provably
+/// linear in the patched values and by brute force otherwise. Values the
+/// program faults on, say by writing outside its memory, aren't solutions.
+pub fn search(program: &Program, query: &Query) -> Found {
+    match linear(program, query) {
+        Some(solutions) => Found {
+            strategy: Strategy::Linear,
+            solutions,
+        },
+        None => Found {
+            strategy: Strategy::BruteForce,
+            solutions: brute_force(program, query),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patches(addrs: &[usize], domain: RangeInclusive<isize>) -> Vec<Patch> {
+        addrs
+            .iter()
+            .map(|&addr| Patch {
+                addr,
+                domain: domain.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear() {
+        // [0] = 3 * [9] + [10]
+        let p: Program = "1002,9,3,11,1,11,10,0,99,0,0,0".parse().unwrap();
+        let query = Query {
+            patches: patches(&[9, 10], 0..=20),
+            output: 0,
+            target: 10,
+        };
+        let found = search(&p, &query);
+        assert_eq!(found.strategy, Strategy::Linear);
+        assert_eq!(
+            found.solutions,
+            vec![vec![0, 10], vec![1, 7], vec![2, 4], vec![3, 1]]
+        );
+        assert_eq!(found.solutions, brute_force(&p, &query));
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [0] = [[1]] * [[2]], which isn't linear in the two addresses.
+        let p: Program = "2,0,0,0,99".parse().unwrap();
+        let query = Query {
+            patches: patches(&[1, 2], 0..=4),
+            output: 0,
+            target: 4,
+        };
+        let found = search(&p, &query);
+        assert_eq!(found.strategy, Strategy::BruteForce);
+        assert_eq!(found.solutions, vec![vec![0, 0], vec![0, 2], vec![2, 2]]);
+    }
+
+    #[test]
+    fn test_linear_except_one_value() {
+        // [0] = [13] + [14] + ([13] == 7), which a few probe runs take for
+        // linear.
+        let p: Program = "1008,13,7,15,1,13,14,16,1,15,16,0,99,0,0,0,0"
+            .parse()
+            .unwrap();
+        let query = Query {
+            patches: patches(&[13, 14], 0..=20),
+            output: 0,
+            target: 10,
+        };
+        let found = search(&p, &query);
+        assert_eq!(found.strategy, Strategy::BruteForce);
+        let expected: Vec<Vec<isize>> = (0..=20)
+            .cartesian_product(0..=20)
+            .filter(|&(x, y)| x + y + (x == 7) as isize == 10)
+            .map(|(x, y)| vec![x, y])
+            .collect();
+        assert!(expected.contains(&vec![7, 2]));
+        assert_eq!(found.solutions, expected);
+    }
+
+    #[test]
+    fn test_faults() {
+        // [0] = [[1]] + [[2]], reading outside memory past 6.
+        let p: Program = "1,0,0,0,99,0,0".parse().unwrap();
+        let query = Query {
+            patches: patches(&[1, 2], 0..=10),
+            output: 0,
+            target: 99,
+        };
+        assert_eq!(query.run(&p, &[7, 0]), Err(Fault::OutOfBounds(7)));
+        let found = search(&p, &query);
+        assert_eq!(found.strategy, Strategy::BruteForce);
+        assert_eq!(
+            found.solutions,
+            vec![
+                vec![3, 4],
+                vec![4, 3],
+                vec![4, 5],
+                vec![4, 6],
+                vec![5, 4],
+                vec![6, 4]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_patches() {
+        let p: Program = "1,0,0,0,99".parse().unwrap();
+        let query = Query {
+            patches: vec![],
+            output: 0,
+            target: 2,
+        };
+        assert_eq!(search(&p, &query).solutions, vec![Vec::<isize>::new()]);
+    }
+}
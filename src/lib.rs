@@ -0,0 +1,32 @@
+//! Advent of Code 2019 solutions and the intcode VM they share.
+//!
+//! Every day is registered in `DAYS`, which the `aoc2019` binary runs through
+//! `runner`. The solutions can also be used directly through their
+//! `Solution` impls.
+
+pub mod answers;
+pub mod bench;
+pub mod intcode;
+pub mod parse;
+pub mod report;
+pub mod runner;
+#[macro_use]
+pub mod solution;
+
+use solution::Day;
+
+days! {
+    day01::Day01,
+    day02::Day02,
+    day03::Day03,
+    day04::Day04,
+    day05::Day05,
+    day06::Day06,
+    day07::Day07,
+    day08::Day08,
+}
+
+/// The registered day with this number, if it's been solved.
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
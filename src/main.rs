@@ -1,43 +1,160 @@
-use clap::{App, Arg};
-use lazy_static::lazy_static;
-
-use std::{collections::HashMap, str::FromStr, time::Instant};
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod intcode;
-
-lazy_static! {
-    static ref DAYS: HashMap<u8, fn()> = {
-        let mut m = HashMap::<u8, fn()>::new();
-        m.insert(1, day01::run_day);
-        m.insert(2, day02::run_day);
-        m.insert(3, day03::run_day);
-        m.insert(4, day04::run_day);
-        m.insert(5, day05::run_day);
-        m.insert(6, day06::run_day);
-        m.insert(7, day07::run_day);
-        m.insert(8, day08::run_day);
-        m
-    };
-}
+use clap::{App, Arg, ArgMatches, SubCommand};
+use itertools::Itertools;
+use tracing::Level;
 
-fn setup() {
-    #[cfg(feature = "profiler")]
-    thread_profiler::register_thread_with_profiler();
+use std::{io, path::Path, process, str::FromStr, time::Instant};
+
+use aoc2019::{
+    answers::{Answers, ANSWERS_FILE},
+    bench::{self, Baseline},
+    day01::report::{SortKey, SORT_KEYS},
+    report::{self, Format, FORMATS},
+    runner::{self, DayRun},
+    solution::{self, cache, DayResult, DaySpec, Parts, PuzzleInput, CACHE_DIR_VAR, INPUT_DIR_VAR},
+    DAYS,
+};
 
+// Only the CLI needs these, so they stay out of the library.
+mod profile;
+mod scaffold;
+mod watch;
+
+fn setup() {
     env_logger::init();
 }
 
+/// Prints why the run failed, giving the exit code for it.
+fn fail(message: String) -> i32 {
+    eprintln!("{}", message);
+    1
+}
+
+fn count(n: &str) -> Result<(), String> {
+    usize::from_str(n)
+        .map(|_| ())
+        .map_err(|_| format!("{:?} isn't a number", n))
+}
+
+fn positive(n: &str) -> Result<(), String> {
+    match usize::from_str(n) {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err(format!("{:?} isn't a positive number", n)),
+    }
+}
+
+fn percentage(n: &str) -> Result<(), String> {
+    match f64::from_str(n) {
+        Ok(n) if n.is_finite() && n >= 0.0 => Ok(()),
+        _ => Err(format!("{:?} isn't a non-negative percentage", n)),
+    }
+}
+
+/// Checks a `-d` value parses and only names days that are implemented.
+fn implemented(spec: &str) -> Result<(), String> {
+    let spec = DaySpec::from_str(spec)?;
+    let missing: Vec<u8> = spec
+        .days
+        .clone()
+        .filter(|day| aoc2019::day(*day).is_none())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Day(s) {} aren't implemented yet, see AVAILABLE DAYS in --help",
+            missing.iter().join(", ")
+        ))
+    }
+}
+
+/// Prints why each failed day failed, keeping the results of the rest.
+/// Returns whether any day failed.
+fn split_failures(runs: Vec<DayRun>) -> (Vec<DayResult>, bool) {
+    let mut results = Vec::new();
+    let mut failed = false;
+    for run in runs {
+        match run {
+            Ok(result) => results.push(result),
+            Err(failure) => {
+                eprintln!("{}", failure);
+                failed = true;
+            }
+        }
+    }
+    (results, failed)
+}
+
+fn write_report(results: &[DayResult], format: Format) -> Result<(), String> {
+    report::write(&mut io::stdout().lock(), results, format)
+        .map_err(|e| format!("Couldn't write results: {}", e))
+}
+
+/// Prints how each answer compares to the recorded one, returning whether
+/// they all matched.
+fn check_answers(path: &Path, results: &[DayResult]) -> Result<bool, String> {
+    let answers = Answers::load(path).map_err(|e| format!("Couldn't load answers: {}", e))?;
+    let checked = runner::check_answers(&answers, results);
+    for mismatch in &checked.mismatches {
+        eprintln!("MISMATCH {}", mismatch);
+    }
+    for (day, part) in &checked.unknown {
+        eprintln!(
+            "Day {} part {}: no recorded answer for this input",
+            day, part
+        );
+    }
+    let ok = checked.mismatches.is_empty();
+    if ok {
+        eprintln!("All recorded answers match");
+    }
+    Ok(ok)
+}
+
+/// Runs the days and their examples, then again whenever an input or example
+/// file changes, printing how the answers moved. Never returns.
+fn watch_days(days: &[(u8, Parts)], source: &PuzzleInput, jobs: usize, format: Format) -> ! {
+    let paths = || {
+        days.iter()
+            .flat_map(|&(day, _)| watch::paths(day, source))
+            .collect()
+    };
+    let mut stamps = watch::stamps(paths());
+    let mut previous: Option<Vec<DayResult>> = None;
+    loop {
+        let (results, _) = split_failures(runner::run_days(days, source, jobs));
+        if let Err(e) = write_report(&results, format) {
+            eprintln!("{}", e);
+        }
+        for &(day, _) in days {
+            if let Some(registered) = aoc2019::day(day) {
+                for failure in (registered.examples)() {
+                    eprintln!("FAILED {}", failure);
+                }
+            }
+        }
+        if let Some(previous) = &previous {
+            let changes = watch::diff(previous, &results);
+            if changes.is_empty() {
+                eprintln!("No answers changed");
+            }
+            for change in changes {
+                eprintln!("CHANGED {}", change);
+            }
+        }
+        previous = Some(results);
+
+        eprintln!("Watching for changes, press Ctrl-C to stop");
+        stamps = watch::wait_for_change(&stamps, paths);
+    }
+}
+
 fn main() {
     let start = Instant::now();
     setup();
+    let available = format!(
+        "AVAILABLE DAYS:\n    {}",
+        DAYS.iter().map(|d| d.number).join(", ")
+    );
     let app = App::new("AoC 2019")
         .version("1.0")
         .author("Favil Orbedios <favilo@gmail.com>")
@@ -55,41 +172,332 @@ fn main() {
                 .multiple(true)
                 .takes_value(true)
                 .number_of_values(1)
-                .help("Which day to run"),
-        );
+                .value_name("DAYS")
+                .validator(|d| implemented(&d))
+                .help("Which day to run: a day like 5, a range like 3..7, optionally with a part like 5:2"),
+        )
+        .arg(
+            Arg::with_name("part")
+                .short("p")
+                .long("part")
+                .takes_value(true)
+                .possible_values(&["1", "2"])
+                .help("Only run this part of each selected day"),
+        )
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Read the puzzle input from FILE, or stdin if FILE is -. Needs a single day"),
+        )
+        .arg(
+            Arg::with_name("input-dir")
+                .long("input-dir")
+                .takes_value(true)
+                .value_name("DIR")
+                .env(INPUT_DIR_VAR)
+                .help("Read each day's puzzle input from DIR/dayNN.txt. Overridden by --input"),
+        )
+        .arg(
+            Arg::with_name("parse-cache")
+                .long("parse-cache")
+                .takes_value(true)
+                .value_name("DIR")
+                .env(CACHE_DIR_VAR)
+                .help("Cache day 3's parsed input in DIR, skipping parsing until the input or its parser changes. Only applies to day 3; other days always parse"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .help("Compare answers against the answers file, exiting non-zero on a mismatch"),
+        )
+        .arg(
+            Arg::with_name("record")
+                .long("record")
+                .conflicts_with("check")
+                .help("Save the computed answers to the answers file as the new baseline"),
+        )
+        .arg(
+            Arg::with_name("answers")
+                .long("answers")
+                .takes_value(true)
+                .value_name("FILE")
+                .default_value(ANSWERS_FILE)
+                .help("Answers file used by --check and --record"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .possible_values(FORMATS)
+                .default_value("table")
+                .help("How to print the results"),
+        )
+        .arg(
+            Arg::with_name("bench")
+                .long("bench")
+                .takes_value(true)
+                .value_name("N")
+                .validator(|n| positive(&n))
+                .help("Time each day's parse, part 1 and part 2 N times instead of running once"),
+        )
+        .arg(
+            Arg::with_name("warmup")
+                .long("warmup")
+                .takes_value(true)
+                .value_name("N")
+                .default_value("3")
+                .validator(|n| count(&n))
+                .help("Untimed runs of each stage before measuring"),
+        )
+        .arg(
+            Arg::with_name("save-baseline")
+                .long("save-baseline")
+                .takes_value(true)
+                .value_name("FILE")
+                .requires("bench")
+                .help("Save the benchmark results to FILE"),
+        )
+        .arg(
+            Arg::with_name("baseline")
+                .long("baseline")
+                .takes_value(true)
+                .value_name("FILE")
+                .requires("bench")
+                .help("Compare against benchmark results saved in FILE, exiting non-zero on regressions"),
+        )
+        .arg(
+            Arg::with_name("threshold")
+                .long("threshold")
+                .takes_value(true)
+                .value_name("PERCENT")
+                .default_value("10")
+                .validator(|n| percentage(&n))
+                .help("How much slower a median can get before it counts as a regression"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .takes_value(true)
+                .value_name("N")
+                .default_value("1")
+                .validator(|n| positive(&n))
+                .help("Run up to N days, and both parts of each day, at the same time"),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .short("w")
+                .long("watch")
+                .conflicts_with_all(&["bench", "check", "record"])
+                .help("Re-run whenever the selected days' input or example files change"),
+        )
+        .arg(
+            Arg::with_name("profile-out")
+                .long("profile-out")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with("watch")
+                .help("Write a trace of the run to FILE: folded stacks for flamegraphs if it ends in .folded, Chrome trace-event JSON otherwise"),
+        )
+        .arg(
+            Arg::with_name("profile-level")
+                .long("profile-level")
+                .takes_value(true)
+                .possible_values(&["info", "debug", "trace"])
+                .default_value("debug")
+                .help("The most detailed spans to trace with --profile-out; trace adds every intcode instruction when built with --features trace-intcode"),
+        )
+        .subcommand(
+            SubCommand::with_name("fuel-report")
+                .about("Lists the fuel each of day 1's modules needs, with totals")
+                .arg(
+                    Arg::with_name("input")
+                        .short("i")
+                        .long("input")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Read the module masses from FILE instead of src/day01/input, or stdin with -"),
+                )
+                .arg(
+                    Arg::with_name("sort")
+                        .long("sort")
+                        .takes_value(true)
+                        .possible_values(SORT_KEYS)
+                        .default_value("module")
+                        .help("Order the modules by this column"),
+                )
+                .arg(
+                    Arg::with_name("reverse")
+                        .long("reverse")
+                        .help("Largest first"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .short("f")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(FORMATS)
+                        .default_value("table"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("new-day")
+                .about("Creates src/dayNN with a solution template, an empty input and an example, and registers it")
+                .arg(
+                    Arg::with_name("DAY")
+                        .required(true)
+                        .validator(|d| solution::parse_day(&d).map(|_| ())),
+                ),
+        )
+        .after_help(available.as_str());
     let matches = app.get_matches();
-    let days: Vec<u8> = {
-        if matches.is_present("all") {
-            (1..=12).collect()
-        } else {
-            let values = matches.values_of("day");
-            if values.is_none() {
-                vec![]
-            } else {
-                values
-                    .unwrap()
-                    .map(u8::from_str)
-                    .map(Result::unwrap)
-                    .collect()
-            }
+
+    if let Err(code) = run(&matches, start) {
+        process::exit(code);
+    }
+}
+
+/// Does what `matches` asks for, giving the exit code on failure. Anything
+/// that needs cleaning up, like a trace being recorded, is dropped by the
+/// time this returns.
+fn run(matches: &ArgMatches, start: Instant) -> Result<(), i32> {
+    if let Some(fuel) = matches.subcommand_matches("fuel-report") {
+        let source = fuel
+            .value_of("input")
+            .map_or(PuzzleInput::Repo, PuzzleInput::from_arg);
+        let report = runner::fuel_report(
+            &source,
+            SortKey::from_str(fuel.value_of("sort").unwrap()).unwrap(),
+            fuel.is_present("reverse"),
+        )
+        .map_err(|e| fail(format!("Couldn't load input for day 1: {}", e)))?;
+        let format = Format::from_str(fuel.value_of("format").unwrap()).unwrap();
+        report
+            .write(&mut io::stdout().lock(), format)
+            .map_err(|e| fail(format!("Couldn't write the report: {}", e)))?;
+        return Ok(());
+    }
+
+    if let Some(new_day) = matches.subcommand_matches("new-day") {
+        let day = solution::parse_day(new_day.value_of("DAY").unwrap()).unwrap();
+        let files = scaffold::create(Path::new("."), day)
+            .map_err(|e| fail(format!("Couldn't create day {}: {}", day, e)))?;
+        for file in files {
+            println!("Wrote {}", file.display());
         }
+        return Ok(());
+    }
+
+    let specs: Vec<DaySpec> = if matches.is_present("all") {
+        DAYS.iter()
+            .map(|d| DaySpec {
+                days: d.number..=d.number,
+                parts: Parts::BOTH,
+            })
+            .collect()
+    } else {
+        matches
+            .values_of("day")
+            .into_iter()
+            .flatten()
+            .map(|spec| DaySpec::from_str(spec).unwrap())
+            .collect()
     };
+    let filter = matches
+        .value_of("part")
+        .map_or(Parts::BOTH, |part| solution::parse_part(part).unwrap());
+    let days = solution::select(&specs, filter);
 
-    log::debug!("Days to cover: {:?}", days);
-    for day in days {
-        if DAYS.contains_key(&day) {
-            let start = Instant::now();
-            log::debug!("Starting day {}", day);
-            DAYS[&day]();
-            log::debug!("Day {} timing: {:?}", day, start.elapsed());
+    if matches.is_present("watch") && matches.value_of("input") == Some("-") {
+        eprintln!("--watch can't re-read stdin, use --input FILE instead");
+        return Err(2);
+    }
+
+    let source = runner::input_source(
+        &days,
+        matches.value_of("input"),
+        matches.value_of("input-dir"),
+    )
+    .map_err(|e| {
+        eprintln!("{}", e);
+        2
+    })?;
+
+    if let Some(dir) = matches.value_of("parse-cache") {
+        cache::enable(dir);
+    }
+
+    let _profile = matches
+        .value_of("profile-out")
+        .map(|path| {
+            let level = Level::from_str(matches.value_of("profile-level").unwrap()).unwrap();
+            profile::start(Path::new(path), level)
+                .map_err(|e| fail(format!("Couldn't start profiling to {}: {}", path, e)))
+        })
+        .transpose()?;
+
+    if let Some(runs) = matches.value_of("bench") {
+        let warmup = usize::from_str(matches.value_of("warmup").unwrap()).unwrap();
+        let threshold = f64::from_str(matches.value_of("threshold").unwrap()).unwrap();
+        let baseline = matches
+            .value_of("baseline")
+            .map(|path| Baseline::load(Path::new(path)))
+            .transpose()
+            .map_err(|e| fail(format!("Couldn't load baseline: {}", e)))?;
+        let benches = runner::bench_days(&days, &source, warmup, usize::from_str(runs).unwrap())
+            .map_err(|e| fail(e.to_string()))?;
+        let regressions = bench::write(
+            &mut io::stdout().lock(),
+            &benches,
+            baseline.as_ref(),
+            threshold,
+        )
+        .map_err(|e| fail(format!("Couldn't write results: {}", e)))?;
+        if let Some(path) = matches.value_of("save-baseline") {
+            Baseline { days: benches }
+                .save(Path::new(path))
+                .map_err(|e| fail(format!("Couldn't save baseline to {}: {}", path, e)))?;
+        }
+        if regressions > 0 {
+            return Err(fail(format!("{} stage(s) regressed", regressions)));
         }
+        return Ok(());
     }
+
+    log::debug!("Days to cover: {:?}", days);
+    let jobs = usize::from_str(matches.value_of("jobs").unwrap()).unwrap();
+    let format = Format::from_str(matches.value_of("format").unwrap()).unwrap();
+    if matches.is_present("watch") {
+        watch_days(&days, &source, jobs, format);
+    }
+
+    let (results, failed) = split_failures(runner::run_days(&days, &source, jobs));
     log::info!("Timing: {:?}", start.elapsed());
-    #[cfg(feature = "profiler")]
-    {
-        let output = "./profile.json";
-        println!("Writing output to {}", output);
 
-        thread_profiler::write_profile(output);
+    // Report the days that worked even when some didn't, but don't check or
+    // record a partial run.
+    write_report(&results, format).map_err(fail)?;
+    if failed {
+        return Err(1);
+    }
+
+    let answers_path = Path::new(matches.value_of("answers").unwrap());
+    if matches.is_present("check") && !check_answers(answers_path, &results).map_err(fail)? {
+        return Err(1);
+    }
+    if matches.is_present("record") {
+        runner::record_answers(answers_path, &results).map_err(|e| {
+            fail(format!(
+                "Couldn't record answers to {}: {}",
+                answers_path.display(),
+                e
+            ))
+        })?;
+        eprintln!("Recorded answers to {}", answers_path.display());
     }
+    Ok(())
 }
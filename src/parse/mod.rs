@@ -0,0 +1,325 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// Where and why a puzzle input failed to parse. Lines and columns count
+/// from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A token of the input along with where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    /// Parses the token with `FromStr`, naming `what` was expected on failure.
+    pub fn parse<T: FromStr>(&self, what: &str) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| {
+            if self.text.is_empty() {
+                self.error(format!("expected {}, found nothing", what))
+            } else {
+                self.error(format!("expected {}, found {:?}", what, self.text))
+            }
+        })
+    }
+}
+
+/// Splits each non-blank line on `sep`, trimming whitespace around every
+/// token.
+pub fn tokens(input: &str, sep: char) -> Vec<Vec<Token<'_>>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let mut tokens = Vec::new();
+            let mut offset = 0;
+            for piece in line.split(sep) {
+                let trimmed = piece.trim_start();
+                let column = line[..offset + piece.len() - trimmed.len()].chars().count() + 1;
+                tokens.push(Token {
+                    text: trimmed.trim_end(),
+                    line: i + 1,
+                    column,
+                });
+                offset += piece.len() + sep.len_utf8();
+            }
+            tokens
+        })
+        .collect()
+}
+
+/// `line` without the empty token left by a separator at its end.
+fn without_trailing<'a, 'b>(line: &'b [Token<'a>]) -> &'b [Token<'a>] {
+    match line.split_last() {
+        Some((last, rest)) if last.text.is_empty() && !rest.is_empty() => rest,
+        _ => line,
+    }
+}
+
+/// Numbers separated by `sep`, on any number of lines, e.g. an intcode
+/// program. A separator at the end of a line is allowed.
+pub fn separated<T: FromStr>(input: &str, sep: char) -> Result<Vec<T>, ParseError> {
+    tokens(input, sep)
+        .iter()
+        .flat_map(|line| without_trailing(line))
+        .map(|token| token.parse("a number"))
+        .collect()
+}
+
+/// Numbers separated by any whitespace, including newlines.
+pub fn whitespace_separated<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.split_whitespace().map(move |text| {
+                let offset = text.as_ptr() as usize - line.as_ptr() as usize;
+                Token {
+                    text,
+                    line: i + 1,
+                    column: line[..offset].chars().count() + 1,
+                }
+            })
+        })
+        .map(|token| token.parse("a number"))
+        .collect()
+}
+
+/// A rectangular grid with one cell per character, where `cell` turns a
+/// character into a cell or rejects it.
+pub fn grid<T>(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let row = line
+            .chars()
+            .enumerate()
+            .map(|(j, c)| {
+                cell(c).ok_or_else(|| ParseError::new(i + 1, j + 1, format!("unexpected {:?}", c)))
+            })
+            .collect::<Result<Vec<T>, _>>()?;
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                return Err(ParseError::new(
+                    i + 1,
+                    row.len().min(first.len()) + 1,
+                    format!(
+                        "expected {} cells like the first row, found {}",
+                        first.len(),
+                        row.len()
+                    ),
+                ));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// `A)B` pairs, one per line, such as an orbit map.
+pub fn edges(input: &str) -> Result<Vec<(String, String)>, ParseError> {
+    tokens(input, ')')
+        .into_iter()
+        .map(|line| match line.as_slice() {
+            [a, b] if !a.text.is_empty() && !b.text.is_empty() => {
+                Ok((a.text.to_string(), b.text.to_string()))
+            }
+            [a, b] if a.text.is_empty() => Err(a.error("expected a name before ')'")),
+            [_, b] => Err(b.error("expected a name after ')'")),
+            [a] => Err(a.error(format!("expected A)B, found {:?}", a.text))),
+            [_, _, c, ..] => Err(c.error("expected a single ')'")),
+            [] => unreachable!("Lines always have at least one token"),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A direction and a distance, like `R10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step {
+    pub direction: Direction,
+    pub distance: usize,
+}
+
+impl Step {
+    fn from_token(token: &Token) -> Result<Self, ParseError> {
+        let direction = match token.text.chars().next() {
+            Some('U') => Direction::Up,
+            Some('D') => Direction::Down,
+            Some('L') => Direction::Left,
+            Some('R') => Direction::Right,
+            _ => return Err(token.error(format!("expected U, D, L or R, found {:?}", token.text))),
+        };
+        let distance = Token {
+            text: &token.text[1..],
+            line: token.line,
+            column: token.column + 1,
+        }
+        .parse("a distance")?;
+        Ok(Step {
+            direction,
+            distance,
+        })
+    }
+}
+
+impl FromStr for Step {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Step::from_token(&Token {
+            text: s,
+            line: 1,
+            column: 1,
+        })
+    }
+}
+
+/// One comma separated path of steps per line, like `R8,U5,L5`.
+pub fn paths(input: &str) -> Result<Vec<Vec<Step>>, ParseError> {
+    tokens(input, ',')
+        .iter()
+        .map(|line| {
+            without_trailing(line)
+                .iter()
+                .map(Step::from_token)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separated() {
+        assert_eq!(
+            separated::<isize>("1,-2, 3,\n4\n\n", ','),
+            Ok(vec![1, -2, 3, 4])
+        );
+        assert_eq!(
+            separated::<isize>("1,2\n3,x4", ',')
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 3: expected a number, found \"x4\""
+        );
+        assert_eq!(
+            separated::<isize>("1,,2", ',').unwrap_err(),
+            ParseError::new(1, 3, "expected a number, found nothing")
+        );
+    }
+
+    #[test]
+    fn test_whitespace_separated() {
+        assert_eq!(
+            whitespace_separated::<u64>("12\n 14  1969\n"),
+            Ok(vec![12, 14, 1969])
+        );
+        assert_eq!(
+            whitespace_separated::<u64>("12\n  -3").unwrap_err(),
+            ParseError::new(2, 3, "expected a number, found \"-3\"")
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let digits = |c: char| c.to_digit(10);
+        assert_eq!(grid("12\n34\n", digits), Ok(vec![vec![1, 2], vec![3, 4]]));
+        assert_eq!(
+            grid("12\n3a", digits).unwrap_err(),
+            ParseError::new(2, 2, "unexpected 'a'")
+        );
+        assert_eq!(grid("12\n3", digits).unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn test_edges() {
+        assert_eq!(
+            edges("COM)B\nB)C\n"),
+            Ok(vec![
+                ("COM".to_string(), "B".to_string()),
+                ("B".to_string(), "C".to_string())
+            ])
+        );
+        assert_eq!(
+            edges("COM)B\nBC").unwrap_err(),
+            ParseError::new(2, 1, "expected A)B, found \"BC\"")
+        );
+        assert_eq!(edges("COM)").unwrap_err().column, 5);
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            paths("R8,U5\nL12").unwrap(),
+            vec![
+                vec![
+                    Step {
+                        direction: Direction::Right,
+                        distance: 8
+                    },
+                    Step {
+                        direction: Direction::Up,
+                        distance: 5
+                    }
+                ],
+                vec![Step {
+                    direction: Direction::Left,
+                    distance: 12
+                }]
+            ]
+        );
+        assert_eq!(
+            paths("R8,X5").unwrap_err(),
+            ParseError::new(1, 4, "expected U, D, L or R, found \"X5\"")
+        );
+        assert_eq!(
+            paths("R8,Ux").unwrap_err(),
+            ParseError::new(1, 5, "expected a distance, found \"x\"")
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+use tracing::Level;
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, Registry};
+
+/// How a trace is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Chrome trace-event JSON, for `chrome://tracing` or Perfetto.
+    Chrome,
+    /// Folded stacks, for `inferno-flamegraph` or `flamegraph.pl`.
+    Folded,
+}
+
+impl Format {
+    /// Folded stacks for a `.folded` file, Chrome JSON for anything else.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext == "folded" => Format::Folded,
+            _ => Format::Chrome,
+        }
+    }
+}
+
+/// Keeps the trace being recorded. It's only complete once this is dropped.
+// The guards are only held for what their `Drop` does.
+#[allow(dead_code)]
+pub enum Guard {
+    Chrome(tracing_chrome::FlushGuard),
+    Folded(tracing_flame::FlushGuard<BufWriter<File>>),
+}
+
+/// Records every span up to `level` into `path` from now on, in the format
+/// its extension picks.
+pub fn start(path: &Path, level: Level) -> io::Result<Guard> {
+    let filter = LevelFilter::from_level(level);
+    let (subscriber, guard): (Box<dyn tracing::Subscriber + Send + Sync>, _) =
+        match Format::for_path(path) {
+            Format::Chrome => {
+                let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                    .writer(File::create(path)?)
+                    .include_args(true)
+                    .build();
+                let subscriber = Registry::default().with(layer).with(filter);
+                (Box::new(subscriber), Guard::Chrome(guard))
+            }
+            Format::Folded => {
+                let (layer, guard) = tracing_flame::FlameLayer::with_file(path)
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                let layer = layer.with_threads_collapsed(true);
+                let subscriber = Registry::default().with(layer).with(filter);
+                (Box::new(subscriber), Guard::Folded(guard))
+            }
+        };
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_path() {
+        assert_eq!(Format::for_path(Path::new("trace.json")), Format::Chrome);
+        assert_eq!(
+            Format::for_path(Path::new("out/day3.folded")),
+            Format::Folded
+        );
+        assert_eq!(Format::for_path(Path::new("trace")), Format::Chrome);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::Serialize;
+
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::solution::DayResult;
+
+/// How the runner prints the results it collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+pub const FORMATS: &[&str] = &["table", "json", "csv"];
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Unknown format {:?}, expected one of {:?}",
+                s, FORMATS
+            )),
+        }
+    }
+}
+
+/// One line of output: a single part of a single day.
+#[derive(Debug, Serialize)]
+struct Row<'a> {
+    day: u8,
+    part: u8,
+    answer: &'a str,
+    parse_ns: u128,
+    solve_ns: u128,
+}
+
+fn rows(results: &[DayResult]) -> impl Iterator<Item = Row<'_>> {
+    results.iter().flat_map(|result| {
+        result.parts.iter().map(move |part| Row {
+            day: result.day,
+            part: part.part,
+            answer: &part.answer,
+            parse_ns: result.parse_time.as_nanos(),
+            solve_ns: part.time.as_nanos(),
+        })
+    })
+}
+
+pub fn write(out: &mut impl Write, results: &[DayResult], format: Format) -> io::Result<()> {
+    match format {
+        Format::Table => write_table(out, results),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &rows(results).collect::<Vec<_>>())?;
+            writeln!(out)
+        }
+        Format::Csv => write_csv(out, results),
+    }
+}
+
+fn write_table(out: &mut impl Write, results: &[DayResult]) -> io::Result<()> {
+    let width = rows(results)
+        .flat_map(|row| row.answer.lines().map(str::len))
+        .chain(Some("answer".len()))
+        .max()
+        .unwrap_or(0);
+    writeln!(
+        out,
+        "{:>3}  {:>4}  {:<width$}  {:>12}  {:>12}",
+        "day",
+        "part",
+        "answer",
+        "parse",
+        "solve",
+        width = width
+    )?;
+    for row in rows(results) {
+        // Images and other multi-line answers continue under the answer column.
+        let mut lines = row.answer.trim_matches('\n').lines();
+        writeln!(
+            out,
+            "{:>3}  {:>4}  {:<width$}  {:>12}  {:>12}",
+            row.day,
+            row.part,
+            lines.next().unwrap_or(""),
+            format!("{:.1?}", Duration::from_nanos(row.parse_ns as u64)),
+            format!("{:.1?}", Duration::from_nanos(row.solve_ns as u64)),
+            width = width
+        )?;
+        for line in lines {
+            writeln!(out, "{:>3}  {:>4}  {}", "", "", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_csv(out: &mut impl Write, results: &[DayResult]) -> io::Result<()> {
+    writeln!(out, "day,part,answer,parse_ns,solve_ns")?;
+    for row in rows(results) {
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            row.day,
+            row.part,
+            csv_field(row.answer),
+            row.parse_ns,
+            row.solve_ns
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::solution::PartResult;
+
+    fn results() -> Vec<DayResult> {
+        vec![DayResult {
+            day: 8,
+            input_hash: 0,
+            parse_time: Duration::from_micros(5),
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    answer: "2375".to_string(),
+                    time: Duration::from_micros(7),
+                },
+                PartResult {
+                    part: 2,
+                    answer: "\n# #\n ##".to_string(),
+                    time: Duration::from_micros(9),
+                },
+            ],
+        }]
+    }
+
+    fn render(format: Format) -> String {
+        let mut out = Vec::new();
+        write(&mut out, &results(), format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render(Format::Csv),
+            "day,part,answer,parse_ns,solve_ns\n8,1,2375,5000,7000\n8,2,\"\n# #\n ##\",5000,9000\n"
+        );
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn test_json() {
+        let rows: serde_json::Value = serde_json::from_str(&render(Format::Json)).unwrap();
+        assert_eq!(rows[1]["answer"], "\n# #\n ##");
+        assert_eq!(rows[0]["solve_ns"], 7000);
+    }
+
+    #[test]
+    fn test_table() {
+        let table = render(Format::Table);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("  8     1  2375"));
+        assert!(lines[2].starts_with("  8     2  # #"));
+        assert_eq!(lines[3], "            ##");
+    }
+}
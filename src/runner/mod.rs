@@ -0,0 +1,221 @@
+use rayon::prelude::*;
+
+use std::{fmt, io, path::Path, time::Instant};
+
+use crate::{
+    answers::{Answers, Mismatch},
+    bench::DayBench,
+    day01::{
+        report::{FuelReport, SortKey},
+        Day01,
+    },
+    solution::{self, DayResult, Parts, PuzzleInput},
+};
+
+/// Where the selected days read their input: `input` (`-` for stdin) when
+/// given, which only makes sense for a single day, else `dir`, else the
+/// inputs committed to the repo.
+pub fn input_source(
+    days: &[(u8, Parts)],
+    input: Option<&str>,
+    dir: Option<&str>,
+) -> Result<PuzzleInput, String> {
+    if let Some(input) = input {
+        if days.len() != 1 {
+            return Err(format!(
+                "--input needs exactly one day, got {:?}",
+                days.iter().map(|(day, _)| day).collect::<Vec<_>>()
+            ));
+        }
+        Ok(PuzzleInput::from_arg(input))
+    } else if let Some(dir) = dir {
+        Ok(PuzzleInput::Dir(dir.into()))
+    } else {
+        Ok(PuzzleInput::Repo)
+    }
+}
+
+/// Why a selected day has no result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The day's input couldn't be read or didn't parse.
+    Load { day: u8, error: String },
+    /// The solution panicked.
+    Panic { day: u8, message: String },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Load { day, error } => {
+                write!(f, "Couldn't load input for day {}: {}", day, error)
+            }
+            Failure::Panic { day, message } => write!(f, "Day {} panicked: {}", day, message),
+        }
+    }
+}
+
+/// What running one selected day came to.
+pub type DayRun = Result<DayResult, Failure>;
+
+fn run_day(day: u8, parts: Parts, source: &PuzzleInput, parallel: bool) -> Option<DayRun> {
+    let registered = crate::day(day)?;
+    let start = Instant::now();
+    log::debug!("Starting day {}", day);
+    let run = match solution::catch(|| (registered.run)(source, parts, parallel)) {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(e)) => Err(Failure::Load {
+            day,
+            error: e.to_string(),
+        }),
+        Err(message) => Err(Failure::Panic { day, message }),
+    };
+    log::debug!("Day {} timing: {:?}", day, start.elapsed());
+    Some(run)
+}
+
+/// Runs the given days, on a pool of `jobs` threads when there's more than
+/// one. Runs come back in the order the days were given either way, skipping
+/// days that aren't implemented.
+pub fn run_days(days: &[(u8, Parts)], source: &PuzzleInput, jobs: usize) -> Vec<DayRun> {
+    if jobs == 1 {
+        return days
+            .iter()
+            .filter_map(|&(day, parts)| run_day(day, parts, source, false))
+            .collect();
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Couldn't start thread pool");
+    pool.install(|| {
+        days.par_iter()
+            .filter_map(|&(day, parts)| run_day(day, parts, source, true))
+            .collect()
+    })
+}
+
+/// How a set of results compares to the recorded answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checked {
+    pub mismatches: Vec<Mismatch>,
+    /// Day and part of every answer with nothing recorded for its input.
+    pub unknown: Vec<(u8, u8)>,
+}
+
+pub fn check_answers(answers: &Answers, results: &[DayResult]) -> Checked {
+    let mut checked = Checked::default();
+    for result in results {
+        let (mismatches, unknown) = answers.check(result);
+        checked.mismatches.extend(mismatches);
+        checked
+            .unknown
+            .extend(unknown.into_iter().map(|part| (result.day, part)));
+    }
+    checked
+}
+
+/// Adds the results to the answers in `path` as the new known-good answers.
+pub fn record_answers(path: &Path, results: &[DayResult]) -> io::Result<()> {
+    let mut answers = Answers::load(path)?;
+    for result in results {
+        answers.record(result);
+    }
+    answers.save(path)
+}
+
+/// Benchmarks the given days, stopping at the first whose input can't be
+/// loaded.
+pub fn bench_days(
+    days: &[(u8, Parts)],
+    source: &PuzzleInput,
+    warmup: usize,
+    runs: usize,
+) -> Result<Vec<DayBench>, Failure> {
+    let mut benches = Vec::new();
+    for &(day, parts) in days {
+        if let Some(registered) = crate::day(day) {
+            log::debug!("Benchmarking day {}", day);
+            let bench =
+                (registered.bench)(source, parts, warmup, runs).map_err(|e| Failure::Load {
+                    day,
+                    error: e.to_string(),
+                })?;
+            benches.push(bench);
+        }
+    }
+    Ok(benches)
+}
+
+/// The fuel each of day 1's modules in `source` needs, ordered by `key`.
+pub fn fuel_report(source: &PuzzleInput, key: SortKey, reverse: bool) -> io::Result<FuelReport> {
+    let (_, masses) = solution::load::<Day01>(source)?;
+    let mut report = FuelReport::new(&masses);
+    report.sort(key, reverse);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_input_source() {
+        let one = [(3, Parts::BOTH)];
+        let two = [(3, Parts::BOTH), (4, Parts::BOTH)];
+        assert_eq!(
+            input_source(&one, Some("-"), Some("inputs")),
+            Ok(PuzzleInput::Stdin)
+        );
+        assert!(input_source(&two, Some("day3.txt"), None).is_err());
+        assert_eq!(
+            input_source(&two, None, Some("inputs")),
+            Ok(PuzzleInput::Dir("inputs".into()))
+        );
+        assert_eq!(input_source(&two, None, None), Ok(PuzzleInput::Repo));
+    }
+
+    #[test]
+    fn test_parallel_order() {
+        let days = [5, 1, 4, 2].map(|day| (day, Parts::BOTH));
+        let results: Vec<DayResult> = run_days(&days, &PuzzleInput::Repo, 4)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            results.iter().map(|r| r.day).collect::<Vec<_>>(),
+            vec![5, 1, 4, 2]
+        );
+        for result in &results {
+            assert_eq!(
+                result.parts.iter().map(|p| p.part).collect::<Vec<_>>(),
+                vec![1, 2]
+            );
+            assert!(result.parse_time > Duration::ZERO);
+            assert!(result.parts.iter().all(|p| p.time > Duration::ZERO));
+        }
+
+        let sequential: Vec<DayResult> = run_days(&days, &PuzzleInput::Repo, 1)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for (parallel, sequential) in results.iter().zip(&sequential) {
+            let answers =
+                |r: &DayResult| r.parts.iter().map(|p| p.answer.clone()).collect::<Vec<_>>();
+            assert_eq!(answers(parallel), answers(sequential));
+        }
+    }
+
+    #[test]
+    fn test_failures() {
+        let dir = std::env::temp_dir().join(format!("aoc-runner-{}", std::process::id()));
+        let runs = run_days(&[(1, Parts::BOTH)], &PuzzleInput::Dir(dir), 1);
+        match &runs[..] {
+            [Err(Failure::Load { day: 1, .. })] => {}
+            other => panic!("Expected a load failure, got {:?}", other),
+        }
+    }
+}
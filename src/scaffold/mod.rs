@@ -0,0 +1,126 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use aoc2019::solution::examples;
+
+const TEMPLATE: &str = include_str!("template.txt");
+const EXAMPLE: &str = include_str!("example.txt");
+
+fn module(day: u8) -> String {
+    format!("day{:02}", day)
+}
+
+/// The `mod.rs` of a fresh day, implementing `Solution` with placeholder
+/// parts.
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("{{type}}", &format!("Day{:02}", day))
+        .replace("{{day}}", &day.to_string())
+}
+
+/// Adds `dayNN::DayNN` to the `days!` block of `lib_rs`, keeping it sorted.
+pub fn register(lib_rs: &str, day: u8) -> Result<String, String> {
+    let entry = format!("{}::Day{:02},", module(day), day);
+    let start = lib_rs
+        .find("days! {\n")
+        .ok_or("Couldn't find the days! block")?
+        + "days! {\n".len();
+    let end = start
+        + lib_rs[start..]
+            .find('}')
+            .ok_or("The days! block isn't closed")?;
+    let mut entries: Vec<&str> = lib_rs[start..end]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if entries.contains(&entry.as_str()) {
+        return Err(format!("Day {} is already registered", day));
+    }
+    entries.push(&entry);
+    entries.sort();
+
+    let mut out = lib_rs[..start].to_string();
+    for entry in entries {
+        out.push_str("    ");
+        out.push_str(entry);
+        out.push('\n');
+    }
+    out.push_str(&lib_rs[end..]);
+    Ok(out)
+}
+
+/// Creates the solution, an empty input and an example template for `day`
+/// under `root`, then registers it in `root/src/lib.rs`. Returns the files
+/// it wrote.
+pub fn create(root: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    let dir = root.join("src").join(module(day));
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+    let lib_rs = root.join("src").join("lib.rs");
+    let registered = register(&fs::read_to_string(&lib_rs)?, day)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let example = root.join(examples::dir(day)).join("example.txt");
+    fs::create_dir_all(example.parent().unwrap())?;
+    let files = vec![
+        (dir.join("mod.rs"), render(day)),
+        (dir.join("input"), String::new()),
+        (example, EXAMPLE.to_string()),
+        (lib_rs, registered),
+    ];
+    for (path, contents) in &files {
+        fs::write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "days! {\n    day01::Day01,\n    day10::Day10,\n}\n\npub fn day() {}\n";
+
+    #[test]
+    fn test_render() {
+        let rendered = render(9);
+        assert!(rendered.contains("pub struct Day09;"));
+        assert!(rendered.contains("const DAY: u8 = 9;"));
+        assert!(!rendered.contains("{{"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(LIB, 9).unwrap(),
+            "days! {\n    day01::Day01,\n    day09::Day09,\n    day10::Day10,\n}\n\npub fn day() {}\n"
+        );
+        assert!(register(LIB, 10).is_err());
+        assert!(register("fn main() {}", 9).is_err());
+    }
+
+    #[test]
+    fn test_create() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src").join("lib.rs"), LIB).unwrap();
+
+        let files = create(&root, 9).unwrap();
+        assert_eq!(files.len(), 4);
+        assert!(root.join("src/day09/examples/example.txt").exists());
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("day09::Day09"));
+        assert_eq!(
+            create(&root, 9).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
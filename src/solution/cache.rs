@@ -0,0 +1,170 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use super::{input_hash, Solution};
+use crate::parse::ParseError;
+
+/// Env var naming a directory to cache parsed inputs in, same as
+/// `--parse-cache`.
+pub const CACHE_DIR_VAR: &str = "AOC_PARSE_CACHE";
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Caches parsed inputs in `dir` for the rest of the process. Only the first
+/// call has any effect.
+pub fn enable(dir: impl Into<PathBuf>) {
+    let _ = DIR.set(dir.into());
+}
+
+/// Where parsed inputs are cached, if anywhere.
+pub fn dir() -> Option<&'static Path> {
+    DIR.get().map(PathBuf::as_path)
+}
+
+/// What a cached parse was made from, written ahead of the parsed input.
+/// Anything that doesn't match exactly is a miss, so changing what a day
+/// parses its input into only needs a bump of its `PARSER_VERSION`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Key {
+    day: u8,
+    /// `input_hash` of the raw input.
+    input: u64,
+    parser_version: u32,
+}
+
+impl Key {
+    fn new<S: Solution>(raw: &str) -> Self {
+        Key {
+            day: S::DAY,
+            input: input_hash(raw),
+            parser_version: S::PARSER_VERSION,
+        }
+    }
+
+    fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("day{:02}-{:016x}.bin", self.day, self.input))
+    }
+}
+
+fn read<S: Solution>(dir: &Path, key: &Key) -> Option<S::Input> {
+    let bytes = fs::read(key.path(dir)).ok()?;
+    let (found, rest) = postcard::take_from_bytes::<Key>(&bytes).ok()?;
+    if found != *key {
+        return None;
+    }
+    S::from_cache(rest)
+}
+
+fn write(dir: &Path, key: &Key, input: &[u8]) -> io::Result<()> {
+    let mut bytes = postcard::to_stdvec(key).map_err(|e| io::Error::other(e.to_string()))?;
+    bytes.extend_from_slice(input);
+    fs::create_dir_all(dir)?;
+    fs::write(key.path(dir), bytes)
+}
+
+/// The cached parse of `raw` in `dir`, if there's one from the current
+/// parser.
+pub fn get<S: Solution>(dir: &Path, raw: &str) -> Option<S::Input> {
+    read::<S>(dir, &Key::new::<S>(raw))
+}
+
+/// Caches the parse of `raw` in `dir`, replacing any older entry. Does
+/// nothing for days that don't support caching.
+pub fn put<S: Solution>(dir: &Path, raw: &str, input: &S::Input) -> io::Result<()> {
+    match S::to_cache(input) {
+        Some(bytes) => write(dir, &Key::new::<S>(raw), &bytes),
+        None => Ok(()),
+    }
+}
+
+/// Parses `raw`, going through the cache if it's enabled. A cache that
+/// can't be written is only worth a warning.
+pub fn parse<S: Solution>(raw: &str) -> Result<S::Input, ParseError> {
+    let dir = match dir() {
+        Some(dir) => dir,
+        None => return S::parse(raw),
+    };
+    if let Some(input) = get::<S>(dir, raw) {
+        log::debug!("Day {} input loaded from the parse cache", S::DAY);
+        return Ok(input);
+    }
+    let input = S::parse(raw)?;
+    if let Err(e) = put::<S>(dir, raw, &input) {
+        log::warn!("Couldn't cache day {} input in {:?}: {}", S::DAY, dir, e);
+    }
+    Ok(input)
+}
+
+/// `Solution::to_cache` for any serializable input.
+pub fn encode<T: Serialize>(input: &T) -> Option<Vec<u8>> {
+    postcard::to_stdvec(input).ok()
+}
+
+/// `Solution::from_cache` for any deserializable input.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    postcard::from_bytes(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Numbers;
+
+    impl Solution for Numbers {
+        const DAY: u8 = 99;
+        const PARSER_VERSION: u32 = 2;
+
+        type Input = Vec<u32>;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+            crate::parse::whitespace_separated(input)
+        }
+
+        fn part1(input: &Vec<u32>) -> u32 {
+            input.iter().sum()
+        }
+
+        fn part2(input: &Vec<u32>) -> u32 {
+            input.iter().product()
+        }
+
+        fn to_cache(input: &Vec<u32>) -> Option<Vec<u8>> {
+            encode(input)
+        }
+
+        fn from_cache(bytes: &[u8]) -> Option<Vec<u32>> {
+            decode(bytes)
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+        assert_eq!(get::<Numbers>(&dir, "1 2 3"), None);
+
+        put::<Numbers>(&dir, "1 2 3", &vec![4, 5, 6]).unwrap();
+        assert_eq!(get::<Numbers>(&dir, "1 2 3"), Some(vec![4, 5, 6]));
+        assert_eq!(get::<Numbers>(&dir, "1 2 4"), None);
+
+        // Entries from another parser version are misses.
+        let key = Key::new::<Numbers>("1 2 3");
+        let stale = Key {
+            parser_version: 1,
+            ..key
+        };
+        write(&dir, &stale, &encode(&vec![4, 5, 6]).unwrap()).unwrap();
+        assert_eq!(get::<Numbers>(&dir, "1 2 3"), None);
+
+        fs::write(key.path(&dir), "not postcard").unwrap();
+        assert_eq!(get::<Numbers>(&dir, "1 2 3"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{catch, Solution};
+
+/// A worked example from a puzzle statement, read from
+/// `src/dayNN/examples/<name>.txt`.
+///
+/// The file starts with `part1: <answer>` and/or `part2: <answer>` lines,
+/// then a `---` line, then the example input verbatim. Lines starting with
+/// `#` before the `---` are comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// File name, without the `.txt`.
+    pub name: String,
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Where a day's examples live, relative to the repo root.
+pub fn dir(day: u8) -> PathBuf {
+    Path::new("src")
+        .join(format!("day{:02}", day))
+        .join("examples")
+}
+
+impl Example {
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let mut example = Example {
+            name: name.to_string(),
+            input: String::new(),
+            part1: None,
+            part2: None,
+        };
+        let mut lines = text.lines();
+        loop {
+            let line = lines
+                .next()
+                .ok_or_else(|| format!("{}: missing the --- line before the input", name))?;
+            if line.trim() == "---" {
+                break;
+            }
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find(':') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim().to_string()),
+                None => {
+                    return Err(format!(
+                        "{}: expected `part1: <answer>`, got {:?}",
+                        name, line
+                    ))
+                }
+            };
+            match key {
+                "part1" => example.part1 = Some(value),
+                "part2" => example.part2 = Some(value),
+                _ => return Err(format!("{}: unknown key {:?}", name, key)),
+            }
+        }
+        example.input = lines.map(|line| format!("{}\n", line)).collect();
+        Ok(example)
+    }
+}
+
+/// Loads every example for `day`, sorted by name. A day without an examples
+/// directory has none.
+pub fn load(day: u8) -> io::Result<Vec<Example>> {
+    let entries = match fs::read_dir(dir(day)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            let text = fs::read_to_string(path)?;
+            Example::parse(&name, &text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+/// Runs every example of `S`, returning a description of each wrong answer.
+/// Examples that can't be loaded, and solutions that panic, count as
+/// failures too.
+pub fn check<S: Solution>() -> Vec<String> {
+    match load(S::DAY) {
+        Ok(examples) => run::<S>(&examples),
+        Err(e) => vec![format!("Day {} examples: {}", S::DAY, e)],
+    }
+}
+
+fn run<S: Solution>(examples: &[Example]) -> Vec<String> {
+    let mut failures = Vec::new();
+    for example in examples {
+        if example.part1.is_none() && example.part2.is_none() {
+            continue;
+        }
+        let input = match catch(|| S::parse(&example.input)) {
+            Ok(Ok(input)) => input,
+            Ok(Err(e)) => {
+                failures.push(format!("Day {} example {}: {}", S::DAY, example.name, e));
+                continue;
+            }
+            Err(panic) => {
+                failures.push(format!(
+                    "Day {} example {}: parse panicked: {}",
+                    S::DAY,
+                    example.name,
+                    panic
+                ));
+                continue;
+            }
+        };
+        let answers = [
+            (
+                1,
+                &example.part1,
+                example
+                    .part1
+                    .as_ref()
+                    .map(|_| catch(|| S::part1(&input).to_string())),
+            ),
+            (
+                2,
+                &example.part2,
+                example
+                    .part2
+                    .as_ref()
+                    .map(|_| catch(|| S::part2(&input).to_string())),
+            ),
+        ];
+        for (part, expected, actual) in answers.iter() {
+            match (expected, actual) {
+                (Some(expected), Some(Ok(actual))) if expected != actual => failures.push(format!(
+                    "Day {} example {} part {}: expected {:?}, got {:?}",
+                    S::DAY,
+                    example.name,
+                    part,
+                    expected,
+                    actual
+                )),
+                (_, Some(Err(panic))) => failures.push(format!(
+                    "Day {} example {} part {}: panicked: {}",
+                    S::DAY,
+                    example.name,
+                    part,
+                    panic
+                )),
+                _ => {}
+            }
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let example = Example::parse("orbits", "part2: 4\n\n---\nCOM)B\nB)C\n").unwrap();
+        assert_eq!(example.part1, None);
+        assert_eq!(example.part2, Some("4".to_string()));
+        assert_eq!(example.input, "COM)B\nB)C\n");
+
+        let commented = Example::parse("new", "# part1: 0\n---\n").unwrap();
+        assert_eq!(commented.part1, None);
+
+        assert!(Example::parse("bad", "part1: 1\nCOM)B\n").is_err());
+        assert!(Example::parse("bad", "part3: 1\n---\n").is_err());
+    }
+
+    struct Halves;
+
+    impl Solution for Halves {
+        const DAY: u8 = 99;
+
+        type Input = u32;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(input: &str) -> Result<u32, crate::parse::ParseError> {
+            crate::parse::whitespace_separated(input).map(|n: Vec<u32>| n[0])
+        }
+
+        fn part1(n: &u32) -> u32 {
+            n / 2
+        }
+
+        fn part2(n: &u32) -> u32 {
+            assert!(n.is_multiple_of(2), "{} is odd", n);
+            n / 2
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let even = Example::parse("even", "part1: 2\npart2: 2\n---\n4\n").unwrap();
+        assert!(run::<Halves>(&[even]).is_empty());
+
+        let odd = Example::parse("odd", "part1: 1\npart2: 2\n---\n5\n").unwrap();
+        assert_eq!(
+            run::<Halves>(&[odd]),
+            vec![
+                "Day 99 example odd part 1: expected \"1\", got \"2\"".to_string(),
+                "Day 99 example odd part 2: panicked: 5 is odd".to_string(),
+            ]
+        );
+
+        let empty = Example::parse("empty", "part1: 0\n---\n").unwrap();
+        assert_eq!(run::<Halves>(&[empty]).len(), 1);
+    }
+}
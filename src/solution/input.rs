@@ -0,0 +1,94 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Env var naming a directory of `dayNN.txt` inputs, same as `--input-dir`.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleInput {
+    /// The input committed alongside the solution, `src/dayNN/input`.
+    Repo,
+    /// A specific file, only meaningful when running a single day.
+    File(PathBuf),
+    /// Standard input, only meaningful when running a single day.
+    Stdin,
+    /// A directory holding one `dayNN.txt` per day.
+    Dir(PathBuf),
+}
+
+impl PuzzleInput {
+    /// Interprets an `--input` argument, where `-` means stdin.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            PuzzleInput::Stdin
+        } else {
+            PuzzleInput::File(PathBuf::from(arg))
+        }
+    }
+
+    /// The file this input is read from for `day`, if it's a file at all.
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            PuzzleInput::Repo => Some(
+                Path::new("src")
+                    .join(format!("day{:02}", day))
+                    .join("input"),
+            ),
+            PuzzleInput::File(path) => Some(path.clone()),
+            PuzzleInput::Stdin => None,
+            PuzzleInput::Dir(dir) => Some(dir.join(format!("day{:02}.txt", day))),
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self.path(day) {
+            Some(path) => {
+                log::debug!("Opening file {:?}", path);
+                fs::read_to_string(&path)
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+            }
+            None => {
+                let mut s = String::new();
+                io::stdin().read_to_string(&mut s)?;
+                Ok(s)
+            }
+        }
+    }
+}
+
+/// 64-bit FNV-1a of the input text. Unlike `DefaultHasher` this is fixed
+/// forever, so it's safe to write into files that outlive the binary.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            PuzzleInput::Repo.path(3),
+            Some(Path::new("src").join("day03").join("input"))
+        );
+        assert_eq!(
+            PuzzleInput::Dir("inputs".into()).path(12),
+            Some(Path::new("inputs").join("day12.txt"))
+        );
+        assert_eq!(PuzzleInput::from_arg("-"), PuzzleInput::Stdin);
+        assert_eq!(PuzzleInput::from_arg("-").path(1), None);
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    fmt::Display,
+    io,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::{bench::DayBench, parse::ParseError};
+
+pub mod cache;
+pub mod examples;
+mod input;
+mod select;
+
+pub use cache::CACHE_DIR_VAR;
+pub use input::{input_hash, PuzzleInput, INPUT_DIR_VAR};
+pub use select::{parse_day, parse_part, select, DaySpec, Parts};
+
+/// Declares each `dayNN` module and registers its solution in `DAYS`.
+///
+/// Adding a day is a matter of adding its `module::Type` to the list. Each
+/// day also gets a test running its `examples`.
+macro_rules! days {
+    ($($module:ident :: $solution:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                #[test]
+                fn $module() {
+                    let failures =
+                        $crate::solution::examples::check::<super::$module::$solution>();
+                    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+                }
+            )*
+        }
+
+        /// Every registered day, in the order they were listed.
+        pub static DAYS: &[$crate::solution::Day] = &[
+            $($crate::solution::Day {
+                number: <$module::$solution as $crate::solution::Solution>::DAY,
+                run: $crate::solution::run::<$module::$solution>,
+                bench: $crate::bench::run::<$module::$solution>,
+                examples: $crate::solution::examples::check::<$module::$solution>,
+            },)*
+        ];
+    };
+}
+
+/// A registered day, as collected by `days!`.
+pub struct Day {
+    pub number: u8,
+    pub run: fn(&PuzzleInput, Parts, bool) -> io::Result<DayResult>,
+    /// Times parsing and the given parts, given the number of warm-up and
+    /// measured runs.
+    pub bench: fn(&PuzzleInput, Parts, usize, usize) -> io::Result<DayBench>,
+    /// Checks the day's examples, describing each one that failed.
+    pub examples: fn() -> Vec<String>,
+}
+
+/// One day of the calendar: how to read its puzzle input and how to solve
+/// both parts from it.
+pub trait Solution {
+    const DAY: u8;
+    /// Bump whenever `parse` starts producing something different, so
+    /// inputs in the parse cache get parsed again.
+    const PARSER_VERSION: u32 = 1;
+
+    type Input: Sync;
+    type Part1: Display + Send;
+    type Part2: Display + Send;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+
+    /// The parsed input as parse cache bytes, usually `cache::encode`. Days
+    /// that don't override this, and `from_cache`, are always parsed from
+    /// scratch.
+    fn to_cache(_input: &Self::Input) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn from_cache(_bytes: &[u8]) -> Option<Self::Input> {
+        None
+    }
+}
+
+/// What running one day produced.
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u8,
+    /// `input_hash` of the raw puzzle input the answers came from.
+    pub input_hash: u64,
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: String,
+    pub time: Duration,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let answer = f();
+    (answer, start.elapsed())
+}
+
+/// Runs `f`, turning a panic into its message so one broken solution doesn't
+/// take a whole `--watch` session down with it.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+/// Reads and parses the day's puzzle input, through the parse cache if it's
+/// enabled, naming where it came from if it doesn't parse. Returns the raw
+/// text along with the parsed input.
+pub fn load<S: Solution>(source: &PuzzleInput) -> io::Result<(String, S::Input)> {
+    let raw = source.read(S::DAY)?;
+    let input = cache::parse::<S>(&raw).map_err(|e| {
+        let origin = source
+            .path(S::DAY)
+            .map_or("<stdin>".to_string(), |path| path.display().to_string());
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", origin, e))
+    })?;
+    Ok((raw, input))
+}
+
+/// Loads the day's input, then times and reports parsing and the selected
+/// parts.
+///
+/// With `parallel`, the two parts are solved concurrently on the current
+/// rayon pool, each still timed on its own and traced under the day's span.
+pub fn run<S: Solution>(
+    source: &PuzzleInput,
+    parts: Parts,
+    parallel: bool,
+) -> io::Result<DayResult> {
+    let span = tracing::info_span!("day", day = S::DAY);
+    let _entered = span.enter();
+
+    let start = Instant::now();
+    let (raw, input) = tracing::info_span!("parse").in_scope(|| load::<S>(source))?;
+    let parse_time = start.elapsed();
+    log::debug!("Day {} loading timer: {:?}", S::DAY, parse_time);
+
+    let part1 = || {
+        if parts.part1 {
+            let _span = tracing::info_span!(parent: &span, "part", part = 1).entered();
+            Some(timed(|| S::part1(&input).to_string()))
+        } else {
+            None
+        }
+    };
+    let part2 = || {
+        if parts.part2 {
+            let _span = tracing::info_span!(parent: &span, "part", part = 2).entered();
+            Some(timed(|| S::part2(&input).to_string()))
+        } else {
+            None
+        }
+    };
+    let (part1, part2) = if parallel {
+        rayon::join(part1, part2)
+    } else {
+        (part1(), part2())
+    };
+
+    let mut results = Vec::new();
+    for (part, solved) in [(1, part1), (2, part2)] {
+        if let Some((answer, time)) = solved {
+            log::debug!("Day {} stage {} timer: {:?}", S::DAY, part, time);
+            log::info!("{}", answer);
+            results.push(PartResult { part, answer, time });
+        }
+    }
+
+    Ok(DayResult {
+        day: S::DAY,
+        input_hash: input_hash(&raw),
+        parse_time,
+        parts: results,
+    })
+}
@@ -0,0 +1,179 @@
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+/// Which of a day's two parts to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parts {
+    pub part1: bool,
+    pub part2: bool,
+}
+
+impl Parts {
+    pub const BOTH: Parts = Parts {
+        part1: true,
+        part2: true,
+    };
+    pub const NONE: Parts = Parts {
+        part1: false,
+        part2: false,
+    };
+
+    /// Just `part`, which must be 1 or 2.
+    pub fn only(part: u8) -> Self {
+        match part {
+            1 => Parts {
+                part1: true,
+                part2: false,
+            },
+            2 => Parts {
+                part1: false,
+                part2: true,
+            },
+            _ => panic!("There is no part {}", part),
+        }
+    }
+
+    pub fn union(self, other: Parts) -> Parts {
+        Parts {
+            part1: self.part1 || other.part1,
+            part2: self.part2 || other.part2,
+        }
+    }
+
+    pub fn intersect(self, other: Parts) -> Parts {
+        Parts {
+            part1: self.part1 && other.part1,
+            part2: self.part2 && other.part2,
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Parts::NONE
+    }
+}
+
+/// Parses a part number, as given to `--part` or after the `:` of a
+/// `-d` value.
+pub fn parse_part(s: &str) -> Result<Parts, String> {
+    match s.trim() {
+        "1" => Ok(Parts::only(1)),
+        "2" => Ok(Parts::only(2)),
+        _ => Err(format!("{:?} isn't a part, expected 1 or 2", s)),
+    }
+}
+
+/// One `-d` value: a day or an inclusive range of days, optionally limited
+/// to a single part, e.g. `5`, `3..7`, `3..=7` or `5:2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySpec {
+    pub days: RangeInclusive<u8>,
+    pub parts: Parts,
+}
+
+/// Parses a day number, which has to be in the calendar.
+pub fn parse_day(s: &str) -> Result<u8, String> {
+    match u8::from_str(s.trim()) {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        Ok(day) => Err(format!("There is no day {}, expected 1 to 25", day)),
+        Err(_) => Err(format!("{:?} isn't a day number", s)),
+    }
+}
+
+impl FromStr for DaySpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (days, parts) = match s.find(':') {
+            Some(i) => (&s[..i], parse_part(&s[i + 1..])?),
+            None => (s, Parts::BOTH),
+        };
+        let days = match days.find("..") {
+            Some(i) => {
+                // Both `a..b` and `a..=b` include `b`.
+                let end = &days[i + 2..];
+                let end = end.strip_prefix('=').unwrap_or(end);
+                let (start, end) = (parse_day(&days[..i])?, parse_day(end)?);
+                if start > end {
+                    return Err(format!("Day range {:?} is empty", days));
+                }
+                start..=end
+            }
+            None => {
+                let day = parse_day(days)?;
+                day..=day
+            }
+        };
+        Ok(DaySpec { days, parts })
+    }
+}
+
+impl fmt::Display for DaySpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.days.start() == self.days.end() {
+            write!(f, "{}", self.days.start())?;
+        } else {
+            write!(f, "{}..{}", self.days.start(), self.days.end())?;
+        }
+        match (self.parts.part1, self.parts.part2) {
+            (true, false) => write!(f, ":1"),
+            (false, true) => write!(f, ":2"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Merges `specs` into the parts to run for each day, in day order.
+/// Days that end up with no parts after applying `filter` are dropped.
+pub fn select(specs: &[DaySpec], filter: Parts) -> Vec<(u8, Parts)> {
+    let mut selected: Vec<(u8, Parts)> = Vec::new();
+    for spec in specs {
+        for day in spec.days.clone() {
+            match selected.iter_mut().find(|(d, _)| *d == day) {
+                Some((_, parts)) => *parts = parts.union(spec.parts),
+                None => selected.push((day, spec.parts)),
+            }
+        }
+    }
+    selected.sort_by_key(|(day, _)| *day);
+    selected
+        .into_iter()
+        .map(|(day, parts)| (day, parts.intersect(filter)))
+        .filter(|(_, parts)| !parts.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(s: &str) -> DaySpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(spec("5").days, 5..=5);
+        assert_eq!(spec("3..7").days, 3..=7);
+        assert_eq!(spec("3..=7").days, 3..=7);
+        assert_eq!(spec("5:2").parts, Parts::only(2));
+        assert_eq!(spec("3..7:1").to_string(), "3..7:1");
+
+        assert!("abc".parse::<DaySpec>().is_err());
+        assert!("0".parse::<DaySpec>().is_err());
+        assert!("26".parse::<DaySpec>().is_err());
+        assert!("7..3".parse::<DaySpec>().is_err());
+        assert!("5:3".parse::<DaySpec>().is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let specs = vec![spec("5:2"), spec("2..3"), spec("5:1"), spec("3:2")];
+        assert_eq!(
+            select(&specs, Parts::BOTH),
+            vec![(2, Parts::BOTH), (3, Parts::BOTH), (5, Parts::BOTH)]
+        );
+        assert_eq!(
+            select(&[spec("5:2"), spec("6")], Parts::only(1)),
+            vec![(6, Parts::only(1))]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use aoc2019::solution::{examples, DayResult, PuzzleInput};
+
+/// How often watched files are checked for changes.
+pub const INTERVAL: Duration = Duration::from_millis(500);
+
+/// The files that affect a day's answers: its input and its examples. The
+/// examples directory itself is included so new examples are noticed.
+pub fn paths(day: u8, source: &PuzzleInput) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = source.path(day).into_iter().collect();
+    let dir = examples::dir(day);
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        files.sort();
+        paths.extend(files);
+    }
+    paths.push(dir);
+    paths
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Modification times of `paths`, where a missing file has none.
+pub type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+pub fn stamps(paths: Vec<PathBuf>) -> Stamps {
+    paths
+        .into_iter()
+        .map(|path| {
+            let time = modified(&path);
+            (path, time)
+        })
+        .collect()
+}
+
+/// Blocks until the files listed by `paths` differ from `last`, checking
+/// every `INTERVAL`. Returns the new stamps.
+pub fn wait_for_change(last: &Stamps, paths: impl Fn() -> Vec<PathBuf>) -> Stamps {
+    loop {
+        thread::sleep(INTERVAL);
+        let current = stamps(paths());
+        if current != *last {
+            return current;
+        }
+    }
+}
+
+/// Describes how each answer changed between two runs, one line per part
+/// that's new, changed or gone.
+pub fn diff(previous: &[DayResult], current: &[DayResult]) -> Vec<String> {
+    let answers = |results: &[DayResult]| -> Vec<(u8, u8, String)> {
+        results
+            .iter()
+            .flat_map(|r| {
+                r.parts
+                    .iter()
+                    .map(move |p| (r.day, p.part, p.answer.clone()))
+            })
+            .collect()
+    };
+    let (before, after) = (answers(previous), answers(current));
+    let find = |answers: &[(u8, u8, String)], day, part| {
+        answers
+            .iter()
+            .find(|(d, p, _)| *d == day && *p == part)
+            .map(|(_, _, a)| a.clone())
+    };
+
+    let mut lines = Vec::new();
+    for (day, part, answer) in &after {
+        match find(&before, *day, *part) {
+            Some(old) if old == *answer => {}
+            Some(old) => lines.push(format!(
+                "Day {} part {}: {} -> {}",
+                day,
+                part,
+                old.trim(),
+                answer.trim()
+            )),
+            None => lines.push(format!(
+                "Day {} part {}: {} (new)",
+                day,
+                part,
+                answer.trim()
+            )),
+        }
+    }
+    for (day, part, answer) in &before {
+        if find(&after, *day, *part).is_none() {
+            lines.push(format!(
+                "Day {} part {}: {} (gone)",
+                day,
+                part,
+                answer.trim()
+            ));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use aoc2019::solution::PartResult;
+
+    fn result(day: u8, answers: &[&str]) -> DayResult {
+        DayResult {
+            day,
+            input_hash: 0,
+            parse_time: Duration::default(),
+            parts: answers
+                .iter()
+                .enumerate()
+                .map(|(i, a)| PartResult {
+                    part: i as u8 + 1,
+                    answer: a.to_string(),
+                    time: Duration::default(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = vec![result(1, &["2", "3"]), result(2, &["9"])];
+        let current = vec![result(1, &["2", "4"]), result(3, &["5"])];
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                "Day 1 part 2: 3 -> 4",
+                "Day 3 part 1: 5 (new)",
+                "Day 2 part 1: 9 (gone)"
+            ]
+        );
+        assert!(diff(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_stamps() {
+        let paths = paths(1, &PuzzleInput::Repo);
+        assert_eq!(paths[0], Path::new("src/day01/input"));
+        assert!(paths.contains(&examples::dir(1)));
+        let current = stamps(paths);
+        assert!(current.iter().all(|(_, time)| time.is_some()));
+        assert!(stamps(vec![PathBuf::from("missing")])[0].1.is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use aoc2019::intcode::{
+    amplifier::{self, Topology},
+    asynchronous, disassemble, from_fn, to_fn, Interrupt, Program,
+};
+
+use futures::{channel::mpsc, executor::block_on, stream};
+
+/// Outputs 999 if the input is below 8, 1000 if it equals 8, 1001 otherwise.
+const COMPARE_TO_8: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+#[test]
+fn parse_and_run() {
+    let program: Program = COMPARE_TO_8.parse().unwrap();
+    for (input, expected) in [(7, 999), (8, 1000), (9, 1001)] {
+        let mut p = program.clone();
+        assert_eq!(p.interpret_input(vec![input].into_iter()), vec![expected]);
+    }
+}
+
+#[test]
+fn parse_errors_point_at_the_problem() {
+    let e = "1,2,3\n4,five".parse::<Program>().unwrap_err();
+    assert_eq!((e.line, e.column), (2, 3));
+
+    let tolerant: Program = "1, 0, 0, 0, 99,\n\n".parse().unwrap();
+    assert_eq!(tolerant.snapshot().memory, vec![1, 0, 0, 0, 99]);
+}
+
+#[test]
+fn closures_as_input_and_output() {
+    // Echoes its input until it reads 0.
+    let mut p: Program = "3,9,4,9,1005,9,0,99,0,0".parse().unwrap();
+    let mut collected = Vec::new();
+    // Each input is one less than the last output, starting from 3.
+    p.run(
+        &mut from_fn(|outputs: &[isize]| {
+            Some(match outputs.last() {
+                None => 3,
+                Some(last) => last - 1,
+            })
+        }),
+        &mut to_fn(|value| collected.push(value)),
+    )
+    .unwrap();
+    assert_eq!(collected, vec![3, 2, 1, 0]);
+}
+
+#[test]
+fn stepping_through_interrupts() {
+    let mut p: Program = "3,0,4,0,99".parse().unwrap();
+    assert_eq!(p.run_until_interrupt(), Interrupt::Input);
+    p.provide_input(42);
+    assert_eq!(p.run_until_interrupt(), Interrupt::Output(42));
+    assert_eq!(p.run_until_interrupt(), Interrupt::Halted);
+}
+
+#[test]
+fn amplifier_search() {
+    let p: Program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0"
+        .parse()
+        .unwrap();
+    let best = amplifier::search(&p, &[0, 1, 2, 3, 4], Topology::Chain);
+    assert_eq!(best.phases, vec![4, 3, 2, 1, 0]);
+    assert_eq!(best.signal, 43210);
+}
+
+#[test]
+fn async_streams() {
+    let mut p: Program = "3,9,1001,9,1,9,4,9,99,0".parse().unwrap();
+    let (tx, mut rx) = mpsc::unbounded();
+    block_on(asynchronous::run(&mut p, stream::iter(vec![41]), tx)).unwrap();
+    assert_eq!(rx.try_recv().ok(), Some(42));
+}
+
+#[test]
+fn disassembly() {
+    assert_eq!(
+        disassemble(&[1002, 4, 3, 4], 0),
+        Some(("MULT [4], #3, [4]".to_string(), 4))
+    );
+}
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use aoc2019::{
+    answers::{Answers, ANSWERS_FILE},
+    day,
+    day01::Day01,
+    day06::Day06,
+    solution::{Parts, PuzzleInput, Solution},
+    DAYS,
+};
+
+/// Days without recorded answers for their committed input, like one fresh
+/// from `new-day`, are listed rather than failed.
+#[test]
+fn every_day_matches_the_recorded_answers() {
+    let answers = Answers::load(Path::new(ANSWERS_FILE)).unwrap();
+    let mut checked = 0;
+    for registered in DAYS {
+        let result = (registered.run)(&PuzzleInput::Repo, Parts::BOTH, false).unwrap();
+        let (mismatches, unknown) = answers.check(&result);
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+        checked += result.parts.len() - unknown.len();
+        for part in unknown {
+            eprintln!("Day {} part {} has no recorded answer", result.day, part);
+        }
+    }
+    assert!(checked > 0, "No recorded answers to check");
+}
+
+#[test]
+fn selected_parts_only() {
+    let result = (day(1).unwrap().run)(&PuzzleInput::Repo, Parts::only(2), false).unwrap();
+    assert_eq!(result.parts.len(), 1);
+    assert_eq!(result.parts[0].part, 2);
+    assert!(day(25).is_none());
+}
+
+#[test]
+fn solutions_work_on_their_own() {
+    let modules = Day01::parse("12\n1969\n").unwrap();
+    assert_eq!(Day01::part1(&modules), 2 + 654);
+    assert_eq!(Day01::part2(&modules), 2 + 966);
+
+    let orbits = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN\n";
+    let map = Day06::parse(orbits).unwrap();
+    assert_eq!(Day06::part1(&map), 54);
+    assert_eq!(Day06::part2(&map), 4);
+
+    // Part 1 doesn't need YOU or SAN.
+    assert_eq!(Day06::part1(&Day06::parse("COM)B\n").unwrap()), 1);
+}
+
+#[test]
+fn bad_input_is_an_error() {
+    let e = Day01::parse("12\nfourteen\n").unwrap_err();
+    assert_eq!((e.line, e.column), (2, 1));
+}